@@ -100,6 +100,7 @@ void SubscriberController::connectToStream(QQuickItem *videoSink) {
                       throw app::StartStreamFailed{};
                   }
 
+                  controller_ = controller;
                   status_.setValue(Playing);
               })
         .onFailed(this, [this](const std::exception &e) {
@@ -107,4 +108,13 @@ void SubscriberController::connectToStream(QQuickItem *videoSink) {
             status_.setValue(Failed);
         });
 }
+
+bool SubscriberController::recenter(bool yawOnly) {
+    if (!controller_) {
+        qWarning(logging::sub()) << "Cannot recenter: stream is not playing";
+        return false;
+    }
+
+    return irt::recenter_subscriber_stream(controller_, yawOnly);
+}
 } // namespace app
\ No newline at end of file
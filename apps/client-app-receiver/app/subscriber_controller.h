@@ -5,6 +5,8 @@
 #include <QObject>
 #include <QQuickItem>
 
+#include "bridge/irt.h"
+
 namespace app {
 
 class SubscriberController : public QObject {
@@ -27,6 +29,10 @@ class SubscriberController : public QObject {
 
     Q_INVOKABLE void connectToStream(QQuickItem *videoSink);
 
+    /// Make the direction the listener is currently facing the forward one.
+    /// Returns false if there is no stream, no head-tracking or no motion data yet.
+    Q_INVOKABLE bool recenter(bool yawOnly = false);
+
     [[nodiscard]] QBindable<Status> bindableStatus() const { return &status_; }
 
     [[nodiscard]] Status status() const { return status_.value(); }
@@ -35,6 +41,8 @@ class SubscriberController : public QObject {
     void statusChanged();
 
   private:
+    irt::StreamController *controller_ = nullptr;
+
     Q_OBJECT_BINDABLE_PROPERTY(SubscriberController, Status, status_,
                                &SubscriberController::statusChanged);
 };
@@ -4,6 +4,7 @@ use std::ops::FromResidual;
 
 use tracing::warn;
 
+mod head_tracking;
 mod request;
 mod stream;
 
@@ -0,0 +1,173 @@
+use std::ffi::{self, CString};
+use std::sync::OnceLock;
+
+use tracing::{info, warn};
+
+use irt_ht_api as api;
+use irt_ht_api::ht::{Orientation, Point3, Quaternion};
+
+#[repr(C)]
+struct HtBackendInfo {
+    name: *const ffi::c_char,
+    description: *const ffi::c_char,
+}
+
+/// Orientation pushed by the host application, as a quaternion `w + xi + yj + zk` in the frame
+/// of the project; it doesn't have to be normalized.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct HtOrientation {
+    w: f32,
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+/// Position of the head pushed by the host application, in meters, in the frame of the project.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct HtPosition {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl HtOrientation {
+    /// Unit quaternion of the orientation, unless it's non-finite or (close to) zero.
+    fn to_orientation(self) -> Option<Orientation> {
+        let quaternion = Quaternion::new(self.w, self.x, self.y, self.z);
+
+        if !quaternion.coords.iter().all(|value| value.is_finite()) || quaternion.norm() < 0.5 {
+            warn!("Ignoring invalid host orientation {quaternion:?}");
+            return None;
+        }
+
+        Some(Orientation::new_normalize(quaternion))
+    }
+}
+
+impl HtPosition {
+    fn to_point(self) -> Option<Point3> {
+        let point = Point3::new(self.x, self.y, self.z);
+
+        if !point.coords.iter().all(|value| value.is_finite()) {
+            warn!("Ignoring invalid host position {point:?}");
+            return None;
+        }
+
+        Some(point)
+    }
+}
+
+/// Names and descriptions of the backends, kept alive for the whole program.
+fn backend_strings() -> &'static [(CString, CString)] {
+    static STRINGS: OnceLock<Vec<(CString, CString)>> = OnceLock::new();
+
+    STRINGS.get_or_init(|| {
+        api::backends()
+            .map(|backend| {
+                (
+                    CString::new(backend.name).unwrap(),
+                    CString::new(backend.description).unwrap(),
+                )
+            })
+            .collect()
+    })
+}
+
+#[no_mangle]
+extern "C" fn ht_backend_count() -> usize {
+    backend_strings().len()
+}
+
+/// Fill `info` with the backend at `index`; the strings are valid for the lifetime of
+/// the program. Returns `false` if the index is out of range.
+#[no_mangle]
+#[must_use]
+extern "C" fn ht_backend_info(index: usize, info: *mut HtBackendInfo) -> bool {
+    let Some((name, description)) = backend_strings().get(index) else {
+        return false;
+    };
+
+    unsafe {
+        info.write(HtBackendInfo {
+            name: name.as_ptr(),
+            description: description.as_ptr(),
+        });
+    }
+
+    true
+}
+
+/// Select the backends to try, in order, for the streams created from now on, given as
+/// comma-separated names; null or empty string restores the default selection.
+/// Returns `false`, leaving the selection as is, if any of the names is unknown.
+#[no_mangle]
+#[must_use]
+extern "C" fn select_ht_backends(names: *const ffi::c_char) -> bool {
+    let names = if names.is_null() {
+        ""
+    } else {
+        match unsafe { ffi::CStr::from_ptr(names) }.to_str() {
+            Ok(names) => names,
+            Err(e) => {
+                warn!("Invalid backend names: {e}");
+                return false;
+            }
+        }
+    };
+
+    let names: Vec<_> = names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    match api::select(&names) {
+        Ok(()) => {
+            info!("Selected head-tracking backends: {names:?}");
+            true
+        }
+        Err(e) => {
+            warn!("Failed to select head-tracking backends: {e}");
+            false
+        }
+    }
+}
+
+/// Enable the `host` backend, so that the streams created from now on use the orientation
+/// pushed with [ht_host_push_orientation] or [ht_host_push_pose], if the backend is selected.
+#[no_mangle]
+extern "C" fn ht_host_enable() {
+    api::host_feed();
+}
+
+/// Push the orientation to the streams using the `host` backend.
+/// Returns `false` if it's invalid, or if no stream is currently receiving it.
+#[no_mangle]
+#[must_use]
+extern "C" fn ht_host_push_orientation(orientation: HtOrientation) -> bool {
+    let Some(orientation) = orientation.to_orientation() else {
+        return false;
+    };
+
+    api::host_feed().push_orientation(orientation)
+}
+
+/// Push the orientation along with the position of the head, see [ht_host_push_orientation].
+#[no_mangle]
+#[must_use]
+extern "C" fn ht_host_push_pose(orientation: HtOrientation, position: HtPosition) -> bool {
+    let (Some(orientation), Some(location)) = (orientation.to_orientation(), position.to_point())
+    else {
+        return false;
+    };
+
+    api::host_feed().push_pose(orientation, location)
+}
+
+/// Report that the source of the pushed samples has been lost, until the next push.
+#[no_mangle]
+extern "C" fn ht_host_disconnect() {
+    api::host_feed().disconnect();
+}
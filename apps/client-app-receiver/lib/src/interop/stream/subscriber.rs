@@ -2,6 +2,9 @@ use std::mem::ManuallyDrop;
 use std::str::Utf8Error;
 use std::{ffi, slice};
 
+use irt_ht_api::ht::recenter::RecenterMode;
+use irt_ht_api::ht::{Capabilities, Status};
+
 use crate::stream::subscriber::{self, StreamController};
 use crate::{define_error_code, try_convert};
 
@@ -20,6 +23,53 @@ define_error_code!(
     CreateSubscriberErrorCode::InvalidUtf8
 );
 
+/// Status of the head tracking of a stream, see [Status].
+#[repr(C)]
+#[derive(Copy, Clone)]
+enum HtStatus {
+    /// There is no head-tracking implementation.
+    Disabled = -1,
+    Unknown = 0,
+    Stopped,
+    PermissionPending,
+    Waiting,
+    Active,
+    Disconnected,
+}
+
+/// Features of the motion data, see [Capabilities].
+#[repr(C)]
+struct HtCapabilities {
+    /// Nominal rate of the samples, in Hz, or zero if unknown.
+    sample_rate: f32,
+    angular_velocity: bool,
+    position: bool,
+}
+
+impl From<Option<Status>> for HtStatus {
+    fn from(value: Option<Status>) -> Self {
+        match value {
+            None => Self::Disabled,
+            Some(Status::Unknown) => Self::Unknown,
+            Some(Status::Stopped) => Self::Stopped,
+            Some(Status::PermissionPending) => Self::PermissionPending,
+            Some(Status::Waiting) => Self::Waiting,
+            Some(Status::Active) => Self::Active,
+            Some(Status::Disconnected) => Self::Disconnected,
+        }
+    }
+}
+
+impl From<Capabilities> for HtCapabilities {
+    fn from(value: Capabilities) -> Self {
+        Self {
+            sample_rate: value.sample_rate.unwrap_or(0.0),
+            angular_velocity: value.angular_velocity,
+            position: value.position,
+        }
+    }
+}
+
 #[repr(C)]
 struct MemoryBuffer {
     data: *const u8,
@@ -66,3 +116,67 @@ extern "C" fn setup_subscriber_stream(stream: *mut StreamController) -> bool {
 
     stream.setup().is_ok()
 }
+
+#[no_mangle]
+extern "C" fn recenter_subscriber_stream(stream: *mut StreamController, yaw_only: bool) -> bool {
+    let stream = ManuallyDrop::new(unsafe { Box::from_raw(stream) });
+
+    let mode = if yaw_only {
+        RecenterMode::YawOnly
+    } else {
+        RecenterMode::Full
+    };
+
+    stream.recenter(mode)
+}
+
+#[no_mangle]
+extern "C" fn subscriber_stream_ht_status(stream: *mut StreamController) -> HtStatus {
+    let stream = ManuallyDrop::new(unsafe { Box::from_raw(stream) });
+
+    stream.ht_status().into()
+}
+
+/// Take the oldest of the head-tracking status changes of the stream which haven't been taken
+/// yet into `status`, so that none of the transitions are missed, unlike with
+/// [subscriber_stream_ht_status]. The first change is the status the head tracking has started in.
+/// Returns `false` if there are none left, or head tracking is disabled.
+#[no_mangle]
+#[must_use]
+extern "C" fn subscriber_stream_next_ht_status(
+    stream: *mut StreamController,
+    status: *mut HtStatus,
+) -> bool {
+    let stream = ManuallyDrop::new(unsafe { Box::from_raw(stream) });
+
+    let Some(value) = stream.next_ht_status() else {
+        return false;
+    };
+
+    unsafe {
+        status.write(Some(value).into());
+    }
+
+    true
+}
+
+/// Fill `capabilities` with those of the head tracking of the stream.
+/// Returns `false` if head tracking is disabled.
+#[no_mangle]
+#[must_use]
+extern "C" fn subscriber_stream_ht_capabilities(
+    stream: *mut StreamController,
+    capabilities: *mut HtCapabilities,
+) -> bool {
+    let stream = ManuallyDrop::new(unsafe { Box::from_raw(stream) });
+
+    let Some(value) = stream.ht_capabilities() else {
+        return false;
+    };
+
+    unsafe {
+        capabilities.write(value.into());
+    }
+
+    true
+}
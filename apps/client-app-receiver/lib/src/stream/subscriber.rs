@@ -1,7 +1,8 @@
 use std::error::Error;
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use gst::prelude::*;
 use gst::{glib, BusSyncReply, EventView, MessageView, PadProbeReturn, PadProbeType};
@@ -9,6 +10,10 @@ use tracing::{debug, error, info, warn};
 
 use irt_gst_renderer::HrtfRenderer;
 use irt_ht_api as api;
+use irt_ht_api::ht::filter::{Filtered, OneEuro};
+use irt_ht_api::ht::predict::Predicted;
+use irt_ht_api::ht::recenter::{RecenterMode, Recentered};
+use irt_ht_api::ht::{self, HeadTracker};
 use irt_ht_api::PlatformHeadTracker;
 use irt_spatial::{na::Vector3, Listener, Orientation, Scene, Soundscape};
 
@@ -96,19 +101,32 @@ fn handle_webrtc_pad(
 enum StateChangeMessage {
     StartedPlaying,
     HaveInitialScene(Scene),
+    LatencyChanged,
 }
 
 struct HtThreadConfig {
     handle: JoinHandle<()>,
     sender: Sender<StateChangeMessage>,
+    head_tracker: Arc<SubscriberHeadTracker>,
+    // Status changes, queued until the application takes them
+    statuses: Mutex<Receiver<ht::Status>>,
 }
 
+// Recentered last, so that the prediction isn't thrown off by the recentering
+type SubscriberHeadTracker = Recentered<Predicted<Filtered<PlatformHeadTracker>>>;
+
 pub struct StreamController {
     pipeline: gst::Pipeline,
     ht_thread: Option<HtThreadConfig>,
 }
 
+/// Interval trackers that can't stream natively are polled at, which also bounds the time
+/// the listener takes to reach a sample.
 const SAMPLING_RESOLUTION: Duration = Duration::from_millis(100);
+/// Interval between the intermediate scenes rendered while the listener moves between samples.
+const SMOOTHING_RESOLUTION: Duration = Duration::from_millis(10);
+/// Upper bound of the motion prediction; extrapolating any further does more harm than good.
+const MAX_PREDICTION_HORIZON: Duration = Duration::from_millis(200);
 
 fn wait_for_initial_scene(rx: &Receiver<StateChangeMessage>) -> Option<Scene> {
     let mut playing = false;
@@ -129,6 +147,8 @@ fn wait_for_initial_scene(rx: &Receiver<StateChangeMessage>) -> Option<Scene> {
                 debug!("Have initial scene");
                 scene = Some(s);
             }
+            // The horizon is set once playing anyway
+            StateChangeMessage::LatencyChanged => {}
         }
 
         if playing && scene.is_some() {
@@ -145,8 +165,10 @@ fn initial_listener() -> Listener {
 
 fn ht_thread_fn(
     rx: &Receiver<StateChangeMessage>,
-    head_tracker: &PlatformHeadTracker,
+    pipeline: &gst::Pipeline,
+    head_tracker: &Arc<SubscriberHeadTracker>,
     hrtf_renderer: &HrtfRenderer,
+    status_sender: &Sender<ht::Status>,
 ) {
     debug!("Head-tracking thread has started");
 
@@ -156,40 +178,122 @@ fn ht_thread_fn(
 
     debug!("Unblocked, initial scene: {scene:?}");
 
+    update_prediction_horizon(pipeline, head_tracker);
+
     if let Err(e) = head_tracker.start_motion_updates() {
         error!("Failed to start receiving motion updates: {e}");
         return;
     }
 
+    // Subscribed once started, since the implementation in use may only be known by then
+    let statuses = head_tracker.subscribe_status();
+    let status = head_tracker.status();
+
+    let capabilities = head_tracker.capabilities();
+    info!("Head tracking is {status}; capabilities: {capabilities:?}");
+
+    let _ = status_sender.send(status);
+
     let mut soundscape = Soundscape::new(scene, initial_listener(), hrtf_renderer.clone());
 
+    // Trackers that can't stream natively are polled at the sampling resolution
+    let (samples, polled) = match head_tracker.subscribe() {
+        Some(stream) => (stream, false),
+        None => (
+            ht::stream::poll(head_tracker.clone(), SAMPLING_RESOLUTION),
+            true,
+        ),
+    };
+    // Interval until the first streamed sample is followed by another one
+    let nominal_interval = capabilities
+        .sample_rate
+        .filter(|rate| *rate > 0.0)
+        .map_or(SAMPLING_RESOLUTION, |rate| {
+            Duration::from_secs_f32(1.0 / rate).min(SAMPLING_RESOLUTION)
+        });
+    let mut previous_timestamp = None;
+    let mut last_tick = Instant::now();
+
     loop {
-        if matches!(rx.try_recv(), Err(TryRecvError::Disconnected)) {
-            debug!("Sender has hung up");
-            break;
+        match rx.try_recv() {
+            Ok(StateChangeMessage::LatencyChanged) => {
+                update_prediction_horizon(pipeline, head_tracker)
+            }
+            Ok(message) => debug!("Ignoring {message:?}"),
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => {
+                debug!("Sender has hung up");
+                break;
+            }
+        }
+
+        if let Some(statuses) = &statuses {
+            while let Ok(Some(status)) = statuses.try_recv() {
+                match status {
+                    ht::Status::Disconnected => warn!("Head tracking is {status}"),
+                    _ => info!("Head tracking is {status}"),
+                }
+
+                let _ = status_sender.send(status);
+            }
         }
 
-        match head_tracker.pull_orientation() {
-            Some(q) => {
-                debug!("orientation: q: {q}");
-                soundscape.set_listener(q.into());
+        match samples.recv_timeout(SMOOTHING_RESOLUTION) {
+            Ok(Some(sample)) => {
+                debug!(
+                    "orientation: q: {}, #{}, age: {:?}",
+                    sample.orientation,
+                    sample.sequence,
+                    sample.age()
+                );
+                // The listener reaches each sample by the time the next one is expected, which
+                // is when the next poll happens, or after the interval the tracker has just
+                // delivered the sample at
+                let interval = match previous_timestamp.replace(sample.timestamp) {
+                    _ if polled => SAMPLING_RESOLUTION,
+                    Some(previous) => sample
+                        .timestamp
+                        .saturating_duration_since(previous)
+                        .min(SAMPLING_RESOLUTION),
+                    None => nominal_interval,
+                };
+                soundscape.set_listener_smoothing(Some(interval));
+                // Six-degrees-of-freedom trackers move the listener around, the others only
+                // turn their head
+                let listener = match sample.location {
+                    Some(location) => (location, sample.orientation).into(),
+                    None => sample.orientation.into(),
+                };
+                soundscape.set_listener(listener);
             }
-            None => {
-                debug!("orientation: none");
+            Ok(None) => {}
+            Err(e) => {
+                warn!("Stopped receiving orientation: {e}");
+                break;
             }
         }
 
-        thread::sleep(SAMPLING_RESOLUTION);
+        let now = Instant::now();
+        soundscape.advance(now - last_tick);
+        last_tick = now;
     }
 
+    drop(samples);
+    drop(statuses);
+
     if let Err(e) = head_tracker.stop_motion_updates() {
         warn!("Failed to stop motion updates: {e}");
     }
 
+    let _ = status_sender.send(head_tracker.status());
+
     debug!("Exiting");
 }
 
-fn create_ht_thread(hrtf_renderer: HrtfRenderer) -> Option<HtThreadConfig> {
+fn create_ht_thread(
+    pipeline: gst::Pipeline,
+    hrtf_renderer: HrtfRenderer,
+) -> Option<HtThreadConfig> {
     let Some(head_tracker) = api::platform_impl() else {
         info!("No platform head-tracking implementation: dynamic spatial audio is disabled");
         return None;
@@ -198,13 +302,59 @@ fn create_ht_thread(hrtf_renderer: HrtfRenderer) -> Option<HtThreadConfig> {
     info!("Have platform head-tracking implementation: dynamic spatial audio is enabled");
 
     let (tx, rx) = mpsc::channel();
+    let (status_tx, status_rx) = mpsc::channel();
+    let head_tracker = Filtered::new(head_tracker, OneEuro::default());
+    // The horizon is set once the pipeline reports its latency
+    let head_tracker = Predicted::new(head_tracker, Duration::ZERO);
+    let head_tracker = Arc::new(Recentered::new(head_tracker));
+    let thread_head_tracker = head_tracker.clone();
 
     let handle = thread::Builder::new()
         .name("irt-ht-thread".to_owned())
-        .spawn(move || ht_thread_fn(&rx, &head_tracker, &hrtf_renderer))
+        .spawn(move || {
+            ht_thread_fn(
+                &rx,
+                &pipeline,
+                &thread_head_tracker,
+                &hrtf_renderer,
+                &status_tx,
+            )
+        })
         .unwrap();
 
-    Some(HtThreadConfig { sender: tx, handle })
+    Some(HtThreadConfig {
+        sender: tx,
+        handle,
+        head_tracker,
+        statuses: Mutex::new(status_rx),
+    })
+}
+
+/// Apply the latency the pipeline has reported, and predict the motion that far ahead.
+///
+/// Meant for the head-tracking thread: recalculating the latency queries all the elements,
+/// so it must not be done on a streaming thread.
+fn update_prediction_horizon(pipeline: &gst::Pipeline, head_tracker: &SubscriberHeadTracker) {
+    if let Err(e) = pipeline.recalculate_latency() {
+        warn!("Failed to recalculate pipeline latency: {e}");
+    }
+
+    let mut query = gst::query::Latency::new();
+
+    if !pipeline.query(&mut query) {
+        warn!("Failed to query pipeline latency");
+        return;
+    }
+
+    let (live, min, max) = query.result();
+    let latency = Duration::from_nanos(min.nseconds());
+    let horizon = latency.min(MAX_PREDICTION_HORIZON);
+
+    debug!(
+        "Pipeline latency: live: {live}, min: {min}, max: {max:?}; predicting {horizon:?} ahead"
+    );
+
+    head_tracker.inner().set_horizon(horizon);
 }
 
 fn on_bus_message(
@@ -231,6 +381,10 @@ fn on_bus_message(
 
             let _ = sender.send(StateChangeMessage::StartedPlaying);
         }
+        // Handled off the streaming thread that has posted the message
+        MessageView::Latency(_) => {
+            let _ = sender.send(StateChangeMessage::LatencyChanged);
+        }
         MessageView::Error(error) => {
             error!(
                 "Pipeline error: {}; debug info: {:?}",
@@ -306,7 +460,7 @@ pub fn create(token: &str, widget: glib::ffi::gpointer, hrir_bytes: &[u8]) -> St
         .add_many([src, video_sink])
         .expect("Could not add elements");
 
-    let ht_thread = create_ht_thread(renderer.clone());
+    let ht_thread = create_ht_thread(pipeline.clone(), renderer.clone());
 
     if let Some(config) = ht_thread.as_ref() {
         let pipeline = pipeline.clone();
@@ -343,6 +497,40 @@ impl StreamController {
         self.pipeline.set_state(gst::State::Playing)?;
         Ok(())
     }
+
+    /// Status of the head tracking; [None] if it's disabled.
+    pub fn ht_status(&self) -> Option<ht::Status> {
+        Some(self.ht_thread.as_ref()?.head_tracker.status())
+    }
+
+    /// Take the oldest of the status changes of the head tracking, starting with the status
+    /// it has started in, which haven't been taken yet.
+    ///
+    /// Returns [None] if there are none, or head tracking is disabled.
+    pub fn next_ht_status(&self) -> Option<ht::Status> {
+        let statuses = self.ht_thread.as_ref()?.statuses.lock().unwrap();
+
+        statuses.try_recv().ok()
+    }
+
+    /// Features of the motion data; [None] if head tracking is disabled.
+    pub fn ht_capabilities(&self) -> Option<ht::Capabilities> {
+        Some(self.ht_thread.as_ref()?.head_tracker.capabilities())
+    }
+
+    /// Make the direction the listener is currently facing the forward one.
+    ///
+    /// Returns `false` if head tracking is disabled or has no motion data yet.
+    pub fn recenter(&self, mode: RecenterMode) -> bool {
+        let Some(ht_thread) = &self.ht_thread else {
+            return false;
+        };
+
+        let recentered = ht_thread.head_tracker.recenter(mode);
+        debug!("Recentering ({mode:?}): {recentered}");
+
+        recentered
+    }
 }
 
 impl Drop for StreamController {
@@ -357,7 +545,7 @@ impl Drop for StreamController {
             bus.unset_sync_handler();
         }
 
-        let Some(HtThreadConfig { sender, handle }) = self.ht_thread.take() else {
+        let Some(HtThreadConfig { sender, handle, .. }) = self.ht_thread.take() else {
             debug!("No thread was running - returning immediately");
             return;
         };
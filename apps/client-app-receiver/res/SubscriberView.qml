@@ -1,4 +1,5 @@
 import QtQuick
+import QtQuick.Controls
 import QtQuick.Layouts
 import org.freedesktop.gstreamer.Qt6GLVideoItem 1.0
 
@@ -39,6 +40,17 @@ Item {
                 id: videoSink
                 anchors.fill: parent
             }
+
+            Button {
+                anchors.right: parent.right
+                anchors.bottom: parent.bottom
+                anchors.margins: 12
+
+                text: "Recenter"
+                visible: controller.status === SubscriberController.Playing
+
+                onClicked: controller.recenter(false)
+            }
         }
 
         Rectangle {
@@ -1,8 +1,21 @@
 #![feature(cfg_match)]
 
-use tracing::info;
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::OnceLock;
 
-use irt_ht_interface as ht;
+use irt_ht_host::Feed;
+use irt_ht_osc::{Binding, Format};
+use irt_ht_replay::{Recorder, Replay, ReplayOptions};
+use tracing::{info, warn};
+
+pub use irt_ht_interface as ht;
+pub use registry::{
+    backend, backends, select, selection, Backend, Fallback, UnknownBackend, BACKEND_VAR,
+};
+
+mod registry;
 
 pub type PlatformHeadTracker = Box<dyn ht::HeadTracker + Send + Sync>;
 
@@ -10,22 +23,290 @@ type PlatformHtImpl = Option<PlatformHeadTracker>;
 
 cfg_match! {
     cfg(target_os = "macos") => {
-        fn create_ht_instance() -> PlatformHtImpl
+        const PLATFORM_BACKENDS: &[Backend] = &[Backend {
+            name: "core-motion",
+            description: "Headphone motion from CoreMotion, e.g. AirPods: orientation and \
+                          angular velocity",
+            create: create_core_motion_instance,
+        }];
+
+        fn create_core_motion_instance() -> PlatformHtImpl
         {
             use irt_ht_core_motion::HeadTracker;
             info!("Instantiating CoreMotion implementation");
             Some(Box::new(HeadTracker::new()))
         }
     }
-    _ => {
-        fn create_ht_instance() -> PlatformHtImpl
+    cfg(target_os = "linux") => {
+        const PLATFORM_BACKENDS: &[Backend] = &[Backend {
+            name: "opentrack",
+            description: "Head pose from OpenTrack's \"UDP over network\" output: orientation \
+                          and position",
+            create: create_opentrack_instance,
+        }];
+
+        fn create_opentrack_instance() -> PlatformHtImpl
         {
-            info!("No implementation is available on this platform");
+            use irt_ht_opentrack::HeadTracker;
+
+            let Ok(address) = env::var(OPENTRACK_ADDRESS_VAR) else {
+                info!("Instantiating OpenTrack implementation on the default port");
+                return Some(Box::new(HeadTracker::new()));
+            };
+
+            match address.parse() {
+                Ok(address) => {
+                    info!("Instantiating OpenTrack implementation on {address}");
+                    Some(Box::new(HeadTracker::with_address(address)))
+                }
+                Err(e) => {
+                    warn!("Invalid {OPENTRACK_ADDRESS_VAR} '{address}': {e}");
+                    None
+                }
+            }
+        }
+    }
+    _ => {
+        const PLATFORM_BACKENDS: &[Backend] = &[];
+    }
+}
+
+/// Backends available on every platform, which have to be configured explicitly;
+/// they come first, so that configuring them is enough to use them.
+const PORTABLE_BACKENDS: &[Backend] = &[
+    Backend {
+        name: "replay",
+        description: "Playback of a head-tracking recording, set by IRT_HT_REPLAY",
+        create: create_replay_instance,
+    },
+    Backend {
+        name: "osc",
+        description: "Quaternions or yaw/pitch/roll angles received as OSC messages, \
+                      on the address set by IRT_HT_OSC_ADDRESS",
+        create: create_osc_instance,
+    },
+    Backend {
+        name: "imu",
+        description: "Raw gyroscope/accelerometer/magnetometer readings fused into the \
+                      orientation, read from the serial device or file set by IRT_HT_IMU_PATH",
+        create: create_imu_instance,
+    },
+    Backend {
+        name: "sim",
+        description: "Synthetic orientation following the motion script set by IRT_HT_SIM, \
+                      for demos and tests without sensors",
+        create: create_sim_instance,
+    },
+    Backend {
+        name: "host",
+        description: "Orientation and position pushed by the host application, \
+                      once it has enabled the host feed",
+        create: create_host_instance,
+    },
+];
+
+// Feed of the host backend, created once the host application enables it
+static HOST_FEED: OnceLock<Feed> = OnceLock::new();
+
+/// Address to receive OpenTrack packets on, e.g. `127.0.0.1:4242` (Linux only).
+pub const OPENTRACK_ADDRESS_VAR: &str = "IRT_HT_OPENTRACK_ADDRESS";
+/// Address to receive OSC messages on, e.g. `0.0.0.0:9000`; enables the OSC implementation.
+pub const OSC_ADDRESS_VAR: &str = "IRT_HT_OSC_ADDRESS";
+/// OSC address of `w x y z` quaternions in the Ambisonics frame, instead of
+/// `/SceneRotator/quaternions`.
+pub const OSC_QUATERNION_PATH_VAR: &str = "IRT_HT_OSC_QUATERNION_PATH";
+/// OSC address of `yaw pitch roll` angles in degrees, instead of `/SceneRotator/ypr`.
+pub const OSC_YPR_PATH_VAR: &str = "IRT_HT_OSC_YPR_PATH";
+/// Serial device or file to read raw IMU readings from; enables the IMU implementation.
+pub const IMU_PATH_VAR: &str = "IRT_HT_IMU_PATH";
+/// Rate of the IMU readings, in Hz.
+pub const IMU_RATE_VAR: &str = "IRT_HT_IMU_RATE";
+/// Set to `rad` if the gyroscope reports radians per second rather than degrees.
+pub const IMU_GYRO_UNITS_VAR: &str = "IRT_HT_IMU_GYRO_UNITS";
+/// Motion script to simulate, e.g. `rotate:30@4;nod@3`; enables the simulated implementation.
+///
+/// See [Script](irt_ht_sim::Script) for the syntax.
+pub const SIM_VAR: &str = "IRT_HT_SIM";
+/// Rate of the simulated samples, in Hz.
+pub const SIM_RATE_VAR: &str = "IRT_HT_SIM_RATE";
+/// Path of a recording to play back instead of using the platform implementation.
+pub const REPLAY_VAR: &str = "IRT_HT_REPLAY";
+/// Set to `1` to play the recording in a loop.
+pub const REPLAY_LOOP_VAR: &str = "IRT_HT_REPLAY_LOOP";
+/// Playback speed of the recording, where `1` is real time.
+pub const REPLAY_SPEED_VAR: &str = "IRT_HT_REPLAY_SPEED";
+/// Path of a file to record the head-tracking samples into.
+pub const RECORD_VAR: &str = "IRT_HT_RECORD";
+
+fn replay_options() -> ReplayOptions {
+    let mut options = ReplayOptions {
+        looping: env::var(REPLAY_LOOP_VAR).is_ok_and(|value| value == "1"),
+        ..Default::default()
+    };
+
+    if let Ok(speed) = env::var(REPLAY_SPEED_VAR) {
+        match speed.parse::<f32>() {
+            Ok(speed) if speed.is_finite() && speed > 0.0 => options.speed = speed,
+            _ => warn!("Ignoring invalid {REPLAY_SPEED_VAR}: '{speed}'"),
+        }
+    }
+
+    options
+}
+
+fn create_replay_instance() -> PlatformHtImpl {
+    let path = env::var_os(REPLAY_VAR)?;
+
+    match Replay::open(&path, replay_options()) {
+        Ok(replay) => {
+            info!("Replaying head-tracking recording {path:?}");
+            Some(Box::new(replay))
+        }
+        Err(e) => {
+            warn!("Failed to open head-tracking recording {path:?}: {e}");
+            None
+        }
+    }
+}
+
+fn osc_bindings() -> Vec<Binding> {
+    Binding::scene_rotator()
+        .into_iter()
+        .map(|binding| {
+            let var = match binding.format {
+                Format::Quaternion(_) => OSC_QUATERNION_PATH_VAR,
+                Format::YawPitchRoll => OSC_YPR_PATH_VAR,
+            };
+
+            match env::var(var) {
+                Ok(path) => Binding { path, ..binding },
+                Err(_) => binding,
+            }
+        })
+        .collect()
+}
+
+fn create_osc_instance() -> PlatformHtImpl {
+    let address = env::var(OSC_ADDRESS_VAR).ok()?;
+
+    match address.parse() {
+        Ok(address) => {
+            let bindings = osc_bindings();
+            info!("Instantiating OSC implementation on {address}: {bindings:?}");
+            Some(Box::new(irt_ht_osc::HeadTracker::new(address, bindings)))
+        }
+        Err(e) => {
+            warn!("Invalid {OSC_ADDRESS_VAR} '{address}': {e}");
             None
         }
     }
 }
 
+fn create_imu_instance() -> PlatformHtImpl {
+    use irt_ht_imu::{Config, GyroUnits, HeadTracker};
+
+    let path = env::var_os(IMU_PATH_VAR)?;
+    let mut config = Config::default();
+
+    if let Ok(rate) = env::var(IMU_RATE_VAR) {
+        match rate.parse::<f32>() {
+            Ok(rate) if rate.is_finite() && rate > 0.0 => config.sample_rate = rate,
+            _ => warn!("Ignoring invalid {IMU_RATE_VAR}: '{rate}'"),
+        }
+    }
+
+    if env::var(IMU_GYRO_UNITS_VAR).is_ok_and(|units| units == "rad") {
+        config.gyro_units = GyroUnits::RadiansPerSecond;
+    }
+
+    info!("Instantiating IMU implementation reading {path:?}: {config:?}");
+
+    Some(Box::new(HeadTracker::open(path, config)))
+}
+
+fn create_sim_instance() -> PlatformHtImpl {
+    use irt_ht_sim::{HeadTracker, Script, DEFAULT_SAMPLE_RATE, MAX_SAMPLE_RATE};
+
+    let text = env::var(SIM_VAR).ok()?;
+
+    let script: Script = match text.parse() {
+        Ok(script) => script,
+        Err(e) => {
+            warn!("Invalid {SIM_VAR} '{text}': {e}");
+            return None;
+        }
+    };
+
+    let mut sample_rate = DEFAULT_SAMPLE_RATE;
+
+    if let Ok(rate) = env::var(SIM_RATE_VAR) {
+        match rate.parse::<f32>() {
+            Ok(rate) if rate.is_finite() && rate > 0.0 && rate <= MAX_SAMPLE_RATE => {
+                sample_rate = rate
+            }
+            _ => warn!("Ignoring invalid {SIM_RATE_VAR}: '{rate}'"),
+        }
+    }
+
+    info!("Instantiating simulated implementation at {sample_rate} Hz: '{text}'");
+
+    Some(Box::new(HeadTracker::new(script, sample_rate)))
+}
+
+/// Enable the `host` backend, returning the feed the host application pushes the samples into.
+///
+/// The backend is not configured until this is called; the head trackers created from then on
+/// receive the pushes while their motion updates are running.
+pub fn host_feed() -> &'static Feed {
+    HOST_FEED.get_or_init(|| {
+        info!("Enabled the host head-tracking feed");
+        Feed::new()
+    })
+}
+
+fn create_host_instance() -> PlatformHtImpl {
+    let feed = HOST_FEED.get()?;
+
+    info!("Instantiating host implementation");
+
+    Some(Box::new(irt_ht_host::HeadTracker::with_feed(feed.clone())))
+}
+
+fn with_recorder(head_tracker: PlatformHeadTracker) -> PlatformHeadTracker {
+    let Some(path) = env::var_os(RECORD_VAR) else {
+        return head_tracker;
+    };
+
+    match File::create(&path) {
+        Ok(file) => {
+            info!("Recording head-tracking samples into {path:?}");
+            Box::new(Recorder::new(head_tracker, BufWriter::new(file)))
+        }
+        Err(e) => {
+            warn!("Failed to create head-tracking recording {path:?}: {e}");
+            head_tracker
+        }
+    }
+}
+
+/// Get the head-tracking implementation, which uses the first available of the
+/// [selected](selection) backends.
+///
+/// Besides the platform-specific backends, a recording may be played back, on any platform,
+/// by setting the [REPLAY_VAR] environment variable (see also [REPLAY_LOOP_VAR] and
+/// [REPLAY_SPEED_VAR]); similarly, an OSC head tracker is used if [OSC_ADDRESS_VAR] is set,
+/// and a simulated one if [SIM_VAR] is. The host application may push the samples itself
+/// through the [host_feed].
+/// The samples of the returned implementation are recorded if [RECORD_VAR] is set.
 pub fn platform_impl() -> PlatformHtImpl {
-    create_ht_instance()
+    let selection = selection();
+
+    if selection.is_empty() {
+        info!("No head-tracking backends are selected");
+        return None;
+    }
+
+    let fallback: PlatformHeadTracker = Box::new(Fallback::new(&selection)?);
+
+    Some(with_recorder(fallback))
 }
@@ -0,0 +1,279 @@
+//! # Backend registry
+//!
+//! Every head-tracking implementation available in this build is registered as a [Backend].
+//! By default, all of them are tried in the registry order; the choice can be narrowed down
+//! with the [BACKEND_VAR] environment variable or, at runtime, with [select].
+
+use std::env;
+use std::sync::Mutex;
+
+use tracing::{info, warn};
+
+use crate::ht::stream::{SampleStream, StatusStream};
+use crate::ht::{self, ApiError};
+use crate::{PlatformHeadTracker, PlatformHtImpl, PLATFORM_BACKENDS, PORTABLE_BACKENDS};
+
+/// Comma-separated names of the backends to try, in order, e.g. `osc,core-motion`.
+pub const BACKEND_VAR: &str = "IRT_HT_BACKEND";
+
+/// Head-tracking implementation available in this build.
+#[derive(Debug)]
+pub struct Backend {
+    /// Unique name, used for selection.
+    pub name: &'static str,
+    /// Human-readable description of the backend and what it can report.
+    pub description: &'static str,
+    // Returns None if the backend is not configured, e.g. lacks its environment variables
+    pub(crate) create: fn() -> PlatformHtImpl,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown head-tracking backend '{0}'")]
+pub struct UnknownBackend(pub String);
+
+/// Head tracker using the first of the backends that is available.
+///
+/// Backends reporting [ApiError::NotAvailable] when starting the motion updates are skipped;
+/// every start tries the backends from the first one again, so that a preferred device
+/// connected in the meantime gets picked up.
+pub struct Fallback {
+    candidates: Vec<(&'static Backend, PlatformHeadTracker)>,
+    active: Mutex<usize>,
+}
+
+// Backends chosen at runtime, taking precedence over the environment variable
+static SELECTION: Mutex<Vec<&'static Backend>> = Mutex::new(Vec::new());
+
+impl Backend {
+    /// Instantiate the backend; returns [None] if it's not configured.
+    pub fn create(&self) -> PlatformHtImpl {
+        (self.create)()
+    }
+}
+
+/// All the backends available in this build, in the default order.
+pub fn backends() -> impl Iterator<Item = &'static Backend> {
+    PORTABLE_BACKENDS.iter().chain(PLATFORM_BACKENDS)
+}
+
+pub fn backend(name: &str) -> Option<&'static Backend> {
+    backends().find(|backend| backend.name == name)
+}
+
+/// Make [platform_impl](crate::platform_impl) try the given backends, in order, instead of
+/// the default ones; an empty list restores the default.
+pub fn select(names: &[&str]) -> Result<(), UnknownBackend> {
+    let selection = names
+        .iter()
+        .map(|&name| backend(name).ok_or_else(|| UnknownBackend(name.to_owned())))
+        .collect::<Result<_, _>>()?;
+
+    *SELECTION.lock().unwrap() = selection;
+
+    Ok(())
+}
+
+/// Backends to try, as selected at runtime, via the environment variable or by default.
+pub fn selection() -> Vec<&'static Backend> {
+    let selection = SELECTION.lock().unwrap().clone();
+
+    if !selection.is_empty() {
+        return selection;
+    }
+
+    let Ok(names) = env::var(BACKEND_VAR) else {
+        return backends().collect();
+    };
+
+    names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| {
+            let backend = backend(name);
+
+            if backend.is_none() {
+                warn!("Ignoring unknown backend '{name}' in {BACKEND_VAR}");
+            }
+
+            backend
+        })
+        .collect()
+}
+
+impl Fallback {
+    /// Instantiate the backends, skipping the ones that are not configured; returns [None]
+    /// if none of them is.
+    pub fn new(backends: &[&'static Backend]) -> Option<Self> {
+        let candidates: Vec<_> = backends
+            .iter()
+            .filter_map(|&backend| {
+                let instance = backend.create();
+
+                match &instance {
+                    Some(_) => info!("Instantiated head-tracking backend '{}'", backend.name),
+                    None => info!("Head-tracking backend '{}' is not configured", backend.name),
+                }
+
+                Some((backend, instance?))
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            candidates,
+            active: Mutex::new(0),
+        })
+    }
+
+    /// The backend in use: the one motion updates have been last started with, or the first
+    /// one, if they have never been started.
+    pub fn active_backend(&self) -> &'static Backend {
+        self.candidates[*self.active.lock().unwrap()].0
+    }
+
+    fn active(&self) -> &PlatformHeadTracker {
+        &self.candidates[*self.active.lock().unwrap()].1
+    }
+}
+
+impl ht::HeadTracker for Fallback {
+    fn start_motion_updates(&self) -> Result<(), ht::Error> {
+        for (index, (backend, head_tracker)) in self.candidates.iter().enumerate() {
+            match head_tracker.start_motion_updates() {
+                Ok(()) => {
+                    info!("Using head-tracking backend '{}'", backend.name);
+                    *self.active.lock().unwrap() = index;
+                    return Ok(());
+                }
+                Err(ht::Error::Api(ApiError::NotAvailable)) => {
+                    info!("Head-tracking backend '{}' is not available", backend.name);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(ApiError::NotAvailable.into())
+    }
+
+    fn pull_sample(&self) -> Option<ht::OrientationSample> {
+        self.active().pull_sample()
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        self.active().subscribe()
+    }
+
+    fn capabilities(&self) -> ht::Capabilities {
+        self.active().capabilities()
+    }
+
+    fn status(&self) -> ht::Status {
+        self.active().status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        self.active().subscribe_status()
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
+        self.active().stop_motion_updates()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::ht::HeadTracker as _;
+
+    /// Tracker starting with the given result, and reporting identity afterwards.
+    struct Stub(fn() -> Result<(), ht::Error>);
+
+    impl ht::HeadTracker for Stub {
+        fn start_motion_updates(&self) -> Result<(), ht::Error> {
+            (self.0)()
+        }
+
+        fn pull_sample(&self) -> Option<ht::OrientationSample> {
+            let orientation = ht::Orientation::identity();
+            Some(ht::OrientationSample::new(orientation, Instant::now(), 0))
+        }
+
+        fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
+            Ok(())
+        }
+    }
+
+    static UNAVAILABLE: Backend = Backend {
+        name: "unavailable",
+        description: "",
+        create: || Some(Box::new(Stub(|| Err(ApiError::NotAvailable.into())))),
+    };
+    static DENIED: Backend = Backend {
+        name: "denied",
+        description: "",
+        create: || Some(Box::new(Stub(|| Err(ApiError::PermissionDenied.into())))),
+    };
+    static AVAILABLE: Backend = Backend {
+        name: "available",
+        description: "",
+        create: || Some(Box::new(Stub(|| Ok(())))),
+    };
+    static UNCONFIGURED: Backend = Backend {
+        name: "unconfigured",
+        description: "",
+        create: || None,
+    };
+
+    #[test]
+    fn test_fallback_skips_unavailable() {
+        let fallback = Fallback::new(&[&UNCONFIGURED, &UNAVAILABLE, &AVAILABLE]).unwrap();
+
+        assert_eq!(fallback.active_backend().name, "unavailable");
+
+        fallback.start_motion_updates().unwrap();
+
+        assert_eq!(fallback.active_backend().name, "available");
+        assert!(fallback.pull_sample().is_some());
+    }
+
+    #[test]
+    fn test_fallback_errors() {
+        let denied = Fallback::new(&[&DENIED, &AVAILABLE]).unwrap();
+        let unavailable = Fallback::new(&[&UNAVAILABLE]).unwrap();
+
+        // Only unavailable backends are skipped
+        assert!(matches!(
+            denied.start_motion_updates(),
+            Err(ht::Error::Api(ApiError::PermissionDenied))
+        ));
+        assert!(matches!(
+            unavailable.start_motion_updates(),
+            Err(ht::Error::Api(ApiError::NotAvailable))
+        ));
+        assert!(Fallback::new(&[&UNCONFIGURED]).is_none());
+    }
+
+    #[test]
+    fn test_select() {
+        let names: Vec<_> = backends().map(|backend| backend.name).collect();
+
+        assert!(names.contains(&"replay") && names.contains(&"osc"));
+        assert_eq!(
+            select(&["osc", "nonexistent"]),
+            Err(UnknownBackend("nonexistent".to_owned()))
+        );
+
+        select(&["osc"]).unwrap();
+        assert_eq!(selection().len(), 1);
+        assert_eq!(selection()[0].name, "osc");
+
+        select(&[]).unwrap();
+        assert!(SELECTION.lock().unwrap().is_empty());
+    }
+}
@@ -1,4 +1,14 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use irt_ht_interface as ht;
+use irt_ht_interface::stream::{StatusReporter, StatusStream};
+use irt_lin_alg::na::Vector3;
+use irt_lin_alg::Frame;
+
+/// Reference frame of the attitude reported by CoreMotion: x-axis points to the right,
+/// y-axis points up, z-axis points forward.
+const CORE_MOTION_FRAME: Frame = Frame::LH_Y_UP;
 
 #[swift_bridge::bridge]
 mod ffi {
@@ -33,22 +43,53 @@ mod ffi {
         z: f64,
     }
 
-    enum Orientation {
-        Some(Quaternion),
+    #[swift_bridge(swift_repr = "struct")]
+    struct RotationRate {
+        x: f64,
+        y: f64,
+        z: f64,
+    }
+
+    #[swift_bridge(swift_repr = "struct")]
+    struct Motion {
+        attitude: Quaternion,
+        rotation_rate: RotationRate,
+        // Time the motion has been captured at, in seconds since the system boot
+        timestamp: f64,
+        // Time elapsed since the motion has been captured, in seconds
+        age: f64,
+    }
+
+    enum MotionSample {
+        Some(Motion),
         None,
     }
 
+    enum DeviceEvent {
+        // Motion updates are starting, but the user is yet to grant the access to motion data
+        PermissionPending,
+        Connected,
+        Disconnected,
+    }
+
+    extern "Rust" {
+        type StatusSink;
+
+        #[swift_bridge(swift_name = "onDeviceEvent")]
+        fn on_device_event(&self, event: DeviceEvent);
+    }
+
     extern "Swift" {
         type CoreMotionHeadTracker;
 
         #[swift_bridge(init)]
-        fn new() -> CoreMotionHeadTracker;
+        fn new(status: StatusSink) -> CoreMotionHeadTracker;
 
         #[swift_bridge(swift_name = "startMotionUpdates")]
         fn start_motion_updates(&self) -> StartResult;
 
-        #[swift_bridge(swift_name = "pullOrientation")]
-        fn pull_orientation(&self) -> Orientation;
+        #[swift_bridge(swift_name = "pullMotion")]
+        fn pull_motion(&self) -> MotionSample;
 
         #[swift_bridge(swift_name = "stopMotionUpdates")]
         fn stop_motion_updates(&self) -> StopResult;
@@ -57,6 +98,46 @@ mod ffi {
 
 pub struct HeadTracker {
     internal: ffi::CoreMotionHeadTracker,
+    samples: Mutex<SampleCounter>,
+    status: Arc<StatusReporter>,
+}
+
+/// Receives the headphone connection events from CoreMotion.
+struct StatusSink(Arc<StatusReporter>);
+
+impl StatusSink {
+    fn on_device_event(&self, event: ffi::DeviceEvent) {
+        use ffi::DeviceEvent;
+
+        let status = match event {
+            DeviceEvent::PermissionPending => ht::Status::PermissionPending,
+            DeviceEvent::Connected => ht::Status::Active,
+            DeviceEvent::Disconnected => ht::Status::Disconnected,
+        };
+
+        // Headphones come and go regardless of the motion updates
+        if self.0.get() != ht::Status::Stopped {
+            self.0.set(status);
+        }
+    }
+}
+
+/// Assigns sequence numbers to the samples, which CoreMotion only tells apart by timestamps.
+#[derive(Default)]
+struct SampleCounter {
+    timestamp: Option<f64>,
+    sequence: u64,
+}
+
+impl SampleCounter {
+    fn sequence_of(&mut self, timestamp: f64) -> u64 {
+        if self.timestamp.is_some_and(|last| last != timestamp) {
+            self.sequence += 1;
+        }
+
+        self.timestamp = Some(timestamp);
+        self.sequence
+    }
 }
 
 unsafe impl Send for HeadTracker {}
@@ -65,9 +146,14 @@ unsafe impl Sync for HeadTracker {}
 
 impl HeadTracker {
     pub fn new() -> Self {
-        let internal = ffi::CoreMotionHeadTracker::new();
+        let status = Arc::new(StatusReporter::default());
+        let internal = ffi::CoreMotionHeadTracker::new(StatusSink(status.clone()));
 
-        Self { internal }
+        Self {
+            internal,
+            samples: Default::default(),
+            status,
+        }
     }
 }
 
@@ -82,47 +168,106 @@ impl ht::HeadTracker for HeadTracker {
     fn start_motion_updates(&self) -> Result<(), ht::Error> {
         use ffi::StartResult;
 
+        // Set beforehand, so that the events reported while starting take precedence
+        self.status.set(ht::Status::Waiting);
+
         match self.internal.start_motion_updates() {
-            StartResult::Success => Ok(()),
-            StartResult::Failure(e) => Err(e.into()),
+            StartResult::Success => {
+                *self.samples.lock().unwrap() = Default::default();
+                Ok(())
+            }
+            StartResult::Failure(e) => {
+                self.status.set(ht::Status::Stopped);
+                Err(e.into())
+            }
         }
     }
 
-    fn pull_orientation(&self) -> Option<ht::UnitQuaternion> {
-        use ffi::Orientation;
+    fn pull_sample(&self) -> Option<ht::OrientationSample> {
+        use ffi::MotionSample;
+
+        let MotionSample::Some(motion) = self.internal.pull_motion() else {
+            return None;
+        };
+
+        let now = Instant::now();
+        let timestamp = now
+            .checked_sub(Duration::from_secs_f64(motion.age.max(0.0)))
+            .unwrap_or(now);
+
+        let sequence = self.samples.lock().unwrap().sequence_of(motion.timestamp);
 
-        match self.internal.pull_orientation() {
-            Orientation::Some(q) => Some(q.into()),
-            Orientation::None => None,
+        // The headphones may have been connected before the motion updates started, in which
+        // case there is no connection event
+        if matches!(
+            self.status.get(),
+            ht::Status::Waiting | ht::Status::PermissionPending
+        ) {
+            self.status.set(ht::Status::Active);
+        }
+
+        let rate = motion.rotation_rate;
+        let rate = Vector3::new(rate.x as f32, rate.y as f32, rate.z as f32);
+
+        // Rotation rate is an axis scaled by the angle, so it's converted like the axis of
+        // the attitude, where mirroring and inversion cancel each other out
+        let angular_velocity = CORE_MOTION_FRAME.convert_vector(&Frame::IRT, &rate);
+
+        Some(
+            ht::OrientationSample::new(motion.attitude.into(), timestamp, sequence)
+                .with_angular_velocity(angular_velocity),
+        )
+    }
+
+    fn capabilities(&self) -> ht::Capabilities {
+        ht::Capabilities {
+            angular_velocity: true,
+            ..Default::default()
         }
     }
 
+    /// The status follows the headphones connecting and disconnecting while the motion
+    /// updates are running.
+    fn status(&self) -> ht::Status {
+        self.status.get()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        Some(self.status.subscribe())
+    }
+
     fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
         use ffi::StopResult;
 
-        let StopResult::Failure(e) = self.internal.stop_motion_updates() else {
-            return Ok(());
+        let result = match self.internal.stop_motion_updates() {
+            StopResult::Success => Ok(()),
+            StopResult::Failure(e) => Err(e.into()),
         };
 
-        Err(e.into())
+        self.status.set(ht::Status::Stopped);
+
+        result
     }
 }
 
 // region FFI->interface conversions
 impl From<ffi::Quaternion> for ht::UnitQuaternion {
     fn from(value: ffi::Quaternion) -> Self {
-        // This essentially does two conversions at the same time:
-        // 1. ffi::Quaternion -> ht::Quaternion
-        // 2. RH Cartesian (CoreMotion) -> LH Cartesian (irt_ht_interface)
         let q = ht::Quaternion::new(
             value.w as f32,
             value.x as f32,
-            value.z as f32, // yz swapped on purpose, see (2) above
             value.y as f32,
+            value.z as f32,
         );
 
         // CoreMotion already outputs unit quaternions, so normalization is skipped
-        Self::new_unchecked(q)
+        let attitude = Self::new_unchecked(q);
+
+        // The interface expects the rotation in the opposite direction to the one reported
+        // by CoreMotion
+        CORE_MOTION_FRAME
+            .convert_orientation(&Frame::IRT, &attitude)
+            .inverse()
     }
 }
 
@@ -10,10 +10,18 @@ extension Quaternion {
     }
 }
 
+extension RotationRate {
+    init(_ r: CMRotationRate) {
+        self.init(x: r.x, y: r.y, z: r.z)
+    }
+}
+
 class CoreMotionHeadTracker: NSObject, CMHeadphoneMotionManagerDelegate {
     let motionService = CMHeadphoneMotionManager()
+    let status: StatusSink
 
-    override init() {
+    init(status: StatusSink) {
+        self.status = status
         super.init()
         motionService.delegate = self
     }
@@ -41,14 +49,20 @@ class CoreMotionHeadTracker: NSObject, CMHeadphoneMotionManagerDelegate {
         return StartResult.Success
     }
 
-    func pullOrientation() -> Orientation {
-        let q = motionService.deviceMotion?.attitude.quaternion;
-
-        guard let q else {
-            return Orientation.None;
+    func pullMotion() -> MotionSample {
+        guard let motion = motionService.deviceMotion else {
+            return MotionSample.None
         }
 
-        return Orientation.Some(Quaternion(q))
+        // Device motion timestamps are measured in seconds since the system boot
+        let age = ProcessInfo.processInfo.systemUptime - motion.timestamp
+
+        return MotionSample.Some(Motion(
+            attitude: Quaternion(motion.attitude.quaternion),
+            rotation_rate: RotationRate(motion.rotationRate),
+            timestamp: motion.timestamp,
+            age: age
+        ))
     }
 
     func stopMotionUpdates() -> StopResult {
@@ -64,11 +78,13 @@ class CoreMotionHeadTracker: NSObject, CMHeadphoneMotionManagerDelegate {
     func headphoneMotionManagerDidConnect(_: CMHeadphoneMotionManager) {
         logger.info("Headphone manager has connected")
         logger.debug("isDeviceMotionActive: \(self.motionService.isDeviceMotionActive)")
+        status.onDeviceEvent(DeviceEvent.Connected)
     }
 
     func headphoneMotionManagerDidDisconnect(_: CMHeadphoneMotionManager) {
         logger.info("Headphone manager has disconnected")
         logger.debug("isDeviceMotionActive: \(self.motionService.isDeviceMotionActive)")
+        status.onDeviceEvent(DeviceEvent.Disconnected)
     }
 
     func ensureServiceAvailability() -> Bool {
@@ -86,6 +102,11 @@ class CoreMotionHeadTracker: NSObject, CMHeadphoneMotionManagerDelegate {
         case CMAuthorizationStatus.restricted:
             logger.error("Motion data access is restricted")
             return false
+        case CMAuthorizationStatus.notDetermined:
+            // The user is asked once the motion updates start
+            logger.info("Motion data access permission is yet to be granted: continuing")
+            status.onDeviceEvent(DeviceEvent.PermissionPending)
+            return true
         default:
             logger.info("Motion data access permission is \(authStatus.rawValue): continuing")
             return true
@@ -0,0 +1,197 @@
+//! # Host-driven head-tracking implementation
+//!
+//! Lets the application embedding the client drive the head tracking from its own sources,
+//! e.g. dragging the view with the mouse, a gamepad or a bridge to a phone. The application
+//! pushes the orientation, and optionally the position, into a [Feed]; the head trackers
+//! attached to it deliver them as samples, like any other implementation.
+
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Instant;
+
+use irt_ht_interface as ht;
+use irt_ht_interface::stream::{Publisher, SampleStream, StatusStream};
+use irt_ht_interface::{Orientation, Point3};
+
+/// Handle the host application pushes the samples through.
+///
+/// Every push is delivered to all the head trackers attached to the feed that have their
+/// motion updates running; clones of the feed push to the same trackers.
+#[derive(Clone, Default)]
+pub struct Feed {
+    trackers: Arc<Mutex<Vec<Weak<Shared>>>>,
+}
+
+/// Head tracker delivering the samples pushed into its [Feed].
+pub struct HeadTracker {
+    feed: Feed,
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    publisher: Publisher,
+    session: Mutex<Session>,
+}
+
+#[derive(Default)]
+struct Session {
+    // Sequence number of the next sample
+    sequence: u64,
+    // Whether any of the samples has carried the location
+    position: bool,
+}
+
+impl Feed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push the orientation, in the coordinate system described in
+    /// [HeadTracker::pull_orientation](ht::HeadTracker::pull_orientation).
+    ///
+    /// Returns `false` if no attached head tracker is running, so the sample is dropped.
+    pub fn push_orientation(&self, orientation: Orientation) -> bool {
+        self.push(orientation, None)
+    }
+
+    /// Push the orientation along with the position of the head, in meters.
+    ///
+    /// Returns `false` if no attached head tracker is running, so the sample is dropped.
+    pub fn push_pose(&self, orientation: Orientation, location: Point3) -> bool {
+        self.push(orientation, Some(location))
+    }
+
+    /// Report that the source of the samples has been lost, e.g. the gamepad unplugged;
+    /// the running head trackers are active again with the next push.
+    pub fn disconnect(&self) {
+        for shared in self.trackers() {
+            shared.publisher.disconnect();
+        }
+    }
+
+    /// Whether any of the attached head trackers is running, so the pushes are delivered.
+    pub fn is_active(&self) -> bool {
+        self.trackers()
+            .iter()
+            .any(|shared| shared.publisher.is_running())
+    }
+
+    fn push(&self, orientation: Orientation, location: Option<Point3>) -> bool {
+        let timestamp = Instant::now();
+        let mut delivered = false;
+
+        for shared in self.trackers() {
+            delivered |= shared.push(orientation, location, timestamp);
+        }
+
+        delivered
+    }
+
+    fn attach(&self, shared: &Arc<Shared>) {
+        let mut trackers = self.trackers.lock().unwrap();
+
+        trackers.retain(|tracker| tracker.strong_count() > 0);
+        trackers.push(Arc::downgrade(shared));
+    }
+
+    fn trackers(&self) -> Vec<Arc<Shared>> {
+        self.trackers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+}
+
+impl HeadTracker {
+    /// Head tracker attached to a new feed, see [feed](Self::feed).
+    pub fn new() -> Self {
+        Self::with_feed(Feed::new())
+    }
+
+    /// Head tracker receiving the pushes into the given feed.
+    pub fn with_feed(feed: Feed) -> Self {
+        let shared = Arc::new(Shared::default());
+
+        feed.attach(&shared);
+
+        Self { feed, shared }
+    }
+
+    pub fn feed(&self) -> &Feed {
+        &self.feed
+    }
+}
+
+impl Default for HeadTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shared {
+    fn push(&self, orientation: Orientation, location: Option<Point3>, timestamp: Instant) -> bool {
+        // Held while publishing, so that the concurrent pushes are published in order
+        let mut session = self.session.lock().unwrap();
+
+        let mut sample = ht::OrientationSample::new(orientation, timestamp, session.sequence);
+
+        if let Some(location) = location {
+            sample = sample.with_location(location);
+        }
+
+        let published = self.publisher.publish(sample);
+
+        if published {
+            session.sequence += 1;
+            session.position |= location.is_some();
+        }
+
+        published
+    }
+}
+
+impl ht::HeadTracker for HeadTracker {
+    fn start_motion_updates(&self) -> Result<(), ht::Error> {
+        let mut session = self.shared.session.lock().unwrap();
+
+        if !self.shared.publisher.is_running() {
+            *session = Session::default();
+            self.shared.publisher.start();
+        }
+
+        Ok(())
+    }
+
+    fn pull_sample(&self) -> Option<ht::OrientationSample> {
+        self.shared.publisher.latest()
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        Some(self.shared.publisher.subscribe())
+    }
+
+    /// The position is only reported once the host application has pushed a pose since
+    /// the motion updates have started; there is no nominal sample rate, as the samples arrive
+    /// whenever they are pushed.
+    fn capabilities(&self) -> ht::Capabilities {
+        ht::Capabilities {
+            position: self.shared.session.lock().unwrap().position,
+            ..Default::default()
+        }
+    }
+
+    fn status(&self) -> ht::Status {
+        self.shared.publisher.status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        Some(self.shared.publisher.subscribe_status())
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
+        self.shared.publisher.stop();
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use irt_ht_host::{Feed, HeadTracker};
+use irt_ht_interface::{HeadTracker as _, Orientation, Point3, Status};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn turned(yaw: f32) -> Orientation {
+    Orientation::from_euler_angles(0.0, 0.0, yaw)
+}
+
+#[test]
+fn test_pushed_samples() {
+    let tracker = HeadTracker::new();
+    let feed = tracker.feed().clone();
+
+    // Nothing to deliver to before the motion updates start
+    assert!(!feed.push_orientation(turned(0.1)));
+    assert!(!feed.is_active());
+    assert_eq!(tracker.status(), Status::Stopped);
+
+    tracker.start_motion_updates().unwrap();
+    let stream = tracker.subscribe().unwrap();
+    let statuses = tracker.subscribe_status().unwrap();
+
+    assert!(feed.is_active());
+    assert_eq!(tracker.status(), Status::Waiting);
+    assert_eq!(tracker.pull_sample(), None);
+
+    assert!(feed.push_orientation(turned(0.2)));
+    assert!(!tracker.capabilities().position);
+
+    assert!(feed.push_pose(turned(0.3), Point3::new(0.1, 0.0, -0.05)));
+    assert!(tracker.capabilities().position);
+
+    let first = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+    let second = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+
+    assert_eq!(first.sequence, 0);
+    assert_eq!(first.orientation, turned(0.2));
+    assert_eq!(first.location, None);
+    assert_eq!(second.sequence, 1);
+    assert_eq!(second.location, Some(Point3::new(0.1, 0.0, -0.05)));
+    assert_eq!(tracker.pull_sample(), Some(second));
+
+    feed.disconnect();
+    assert_eq!(tracker.status(), Status::Disconnected);
+
+    assert!(feed.push_orientation(turned(0.4)));
+    assert_eq!(tracker.status(), Status::Active);
+
+    tracker.stop_motion_updates().unwrap();
+    assert!(!feed.push_orientation(turned(0.5)));
+
+    for status in [
+        Status::Active,
+        Status::Disconnected,
+        Status::Active,
+        Status::Stopped,
+    ] {
+        assert_eq!(statuses.try_recv(), Ok(Some(status)));
+    }
+
+    // Restarting forgets the previous session
+    tracker.start_motion_updates().unwrap();
+    assert_eq!(tracker.pull_sample(), None);
+    assert!(!tracker.capabilities().position);
+
+    assert!(feed.push_orientation(turned(0.6)));
+    assert_eq!(tracker.pull_sample().unwrap().sequence, 0);
+}
+
+#[test]
+fn test_shared_feed() {
+    let feed = Feed::new();
+    let first = HeadTracker::with_feed(feed.clone());
+    let second = HeadTracker::with_feed(feed.clone());
+
+    first.start_motion_updates().unwrap();
+    assert!(feed.push_orientation(turned(0.1)));
+
+    // Only the running trackers receive the samples
+    assert!(first.pull_sample().is_some());
+    assert_eq!(second.pull_sample(), None);
+
+    second.start_motion_updates().unwrap();
+    drop(first);
+
+    assert!(feed.push_orientation(turned(0.2)));
+    assert_eq!(second.pull_sample().unwrap().orientation, turned(0.2));
+
+    drop(second);
+    assert!(!feed.push_orientation(turned(0.3)));
+}
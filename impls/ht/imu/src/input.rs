@@ -0,0 +1,110 @@
+use irt_lin_alg::na::Vector3;
+use irt_lin_alg::Frame;
+
+use crate::madgwick::Reading;
+
+/// Units of the angular velocity reported by the gyroscope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GyroUnits {
+    #[default]
+    DegreesPerSecond,
+    RadiansPerSecond,
+}
+
+/// Malformed line of sensor readings.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ParseError {
+    #[error("expected 6 or 9 values, found {0}")]
+    ValueCount(usize),
+    #[error("`{0}` is not a valid number")]
+    InvalidNumber(String),
+}
+
+/// Parse a line of readings: `gx gy gz ax ay az [mx my mz]`, separated by whitespace or
+/// commas, in the frame of the sensor.
+pub fn parse_line(line: &str, units: GyroUnits, frame: &Frame) -> Result<Reading, ParseError> {
+    let values = line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            field
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| ParseError::InvalidNumber(field.to_owned()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if values.len() != 6 && values.len() != 9 {
+        return Err(ParseError::ValueCount(values.len()));
+    }
+
+    let vector = |index: usize| {
+        let vector = Vector3::from_column_slice(&values[index..index + 3]);
+        frame.convert_vector(&Frame::IRT, &vector)
+    };
+
+    let gyro = match units {
+        GyroUnits::DegreesPerSecond => vector(0).map(f32::to_radians),
+        GyroUnits::RadiansPerSecond => vector(0),
+    };
+
+    Ok(Reading {
+        gyro,
+        accel: vector(3),
+        mag: (values.len() == 9).then(|| vector(6)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let reading = parse_line(
+            "180, 0, 0, 0.1, 0.2, 9.8",
+            GyroUnits::default(),
+            &Frame::IRT,
+        );
+
+        assert_eq!(
+            reading,
+            Ok(Reading {
+                gyro: Vector3::new(std::f32::consts::PI, 0.0, 0.0),
+                accel: Vector3::new(0.1, 0.2, 9.8),
+                mag: None,
+            })
+        );
+
+        // Sensor mounted with its y-axis pointing up, z-axis pointing backward
+        let reading = parse_line(
+            "0 1 0  0 9.8 0  1 2 3",
+            GyroUnits::RadiansPerSecond,
+            &Frame::RH_Y_UP,
+        )
+        .unwrap();
+
+        assert_eq!(reading.gyro, Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(reading.accel, Vector3::new(0.0, 0.0, 9.8));
+        assert_eq!(reading.mag, Some(Vector3::new(1.0, -3.0, 2.0)));
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let units = GyroUnits::default();
+
+        assert_eq!(
+            parse_line("MPU6050 ready", units, &Frame::IRT),
+            Err(ParseError::InvalidNumber("MPU6050".to_owned()))
+        );
+        assert_eq!(
+            parse_line("1 2 3 4", units, &Frame::IRT),
+            Err(ParseError::ValueCount(4))
+        );
+        assert_eq!(
+            parse_line("1 2 3 4 5 NaN", units, &Frame::IRT),
+            Err(ParseError::InvalidNumber("NaN".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! # IMU head-tracking implementation
+//!
+//! DIY head trackers, built around boards such as MPU-6050 or BNO055, usually report raw gyroscope,
+//! accelerometer and magnetometer readings rather than the orientation. This implementation
+//! reads such readings, one line at a time, from a serial device or a file, and fuses them
+//! into the orientation with the [Madgwick](madgwick) filter.
+//!
+//! See [input::parse_line] for the expected format of the lines. Serial devices have to be
+//! configured beforehand, e.g. with `stty -F /dev/ttyUSB0 115200 raw`.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use irt_ht_interface as ht;
+use irt_ht_interface::stream::{Publisher, SampleStream, StatusStream};
+use irt_lin_alg::Frame;
+
+pub use input::{GyroUnits, ParseError};
+pub use madgwick::{Madgwick, Reading};
+
+pub mod input;
+pub mod madgwick;
+
+/// Source of the lines of readings, opened every time the motion updates start.
+pub type Source = Box<dyn Fn() -> io::Result<Box<dyn BufRead + Send>> + Send + Sync>;
+
+/// Settings of the sensor and the filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// Rate the readings are taken at, in Hz.
+    pub sample_rate: f32,
+    pub gyro_units: GyroUnits,
+    /// Frame of the sensor, as mounted on the head.
+    pub frame: Frame,
+    /// Gain of the [Madgwick] filter.
+    pub beta: f32,
+}
+
+/// Head tracker fusing raw IMU readings into the orientation.
+pub struct HeadTracker {
+    source: Source,
+    config: Config,
+    // Whether to read the lines at the sample rate, rather than as they come
+    paced: bool,
+    publisher: Arc<Publisher>,
+    running: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            sample_rate: 100.0,
+            gyro_units: GyroUnits::default(),
+            frame: Frame::IRT,
+            beta: 0.1,
+        }
+    }
+}
+
+impl HeadTracker {
+    /// Read from a serial device or a file; regular files are read at the sample rate, as if
+    /// the readings were coming from a device.
+    pub fn open(path: impl Into<PathBuf>, config: Config) -> Self {
+        let path = path.into();
+        let paced = path.metadata().is_ok_and(|metadata| metadata.is_file());
+
+        let source: Source = Box::new(move || {
+            let file = File::open(&path)?;
+            Ok(Box::new(BufReader::new(file)))
+        });
+
+        Self::with_source(source, config, paced)
+    }
+
+    pub fn with_source(source: Source, config: Config, paced: bool) -> Self {
+        Self {
+            source,
+            config,
+            paced,
+            publisher: Arc::default(),
+            running: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+}
+
+fn receive(
+    publisher: &Publisher,
+    lines: Box<dyn BufRead + Send>,
+    config: Config,
+    paced: bool,
+    running: &AtomicBool,
+) {
+    let interval = Duration::from_secs_f32(1.0 / config.sample_rate);
+    let mut filter = Madgwick::new(config.beta);
+    let mut sequence = 0;
+    let start = Instant::now();
+
+    for line in lines.lines() {
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let Ok(line) = line else {
+            break;
+        };
+
+        // Devices print all sorts of status messages, especially on boot
+        let Ok(reading) = input::parse_line(&line, config.gyro_units, &config.frame) else {
+            continue;
+        };
+
+        if paced {
+            let due = start + interval * sequence as u32;
+            thread::sleep(due.saturating_duration_since(Instant::now()));
+        }
+
+        let orientation = filter.update(&reading, interval.as_secs_f32());
+        let sample = ht::OrientationSample::new(orientation, Instant::now(), sequence)
+            .with_angular_velocity(orientation * reading.gyro);
+        sequence += 1;
+
+        // Stopped while waiting for the reading, or even restarted with another thread
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+
+        publisher.publish(sample);
+    }
+
+    // The device has been unplugged, or the file has ended
+    if running.load(Ordering::Relaxed) {
+        publisher.disconnect();
+    }
+}
+
+impl ht::HeadTracker for HeadTracker {
+    fn start_motion_updates(&self) -> Result<(), ht::Error> {
+        let mut running = self.running.lock().unwrap();
+
+        if running.is_some() {
+            return Ok(());
+        }
+
+        let lines = (self.source)().map_err(|e| match e.kind() {
+            // The device is not connected
+            io::ErrorKind::NotFound => ht::ApiError::NotAvailable.into(),
+            _ => ht::Error::from(ht::UnknownError::new(format!(
+                "failed to open sensor readings: {e}"
+            ))),
+        })?;
+
+        self.publisher.start();
+
+        let flag = Arc::new(AtomicBool::new(true));
+
+        thread::Builder::new()
+            .name("irt-ht-imu".to_owned())
+            .spawn({
+                let publisher = self.publisher.clone();
+                let (config, paced, flag) = (self.config, self.paced, flag.clone());
+
+                move || receive(&publisher, lines, config, paced, &flag)
+            })
+            .map_err(|e| {
+                self.publisher.stop();
+                ht::UnknownError::new(format!("failed to spawn reading thread: {e}"))
+            })?;
+
+        *running = Some(flag);
+
+        Ok(())
+    }
+
+    fn pull_sample(&self) -> Option<ht::OrientationSample> {
+        self.publisher.latest()
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        Some(self.publisher.subscribe())
+    }
+
+    fn capabilities(&self) -> ht::Capabilities {
+        ht::Capabilities {
+            sample_rate: Some(self.config.sample_rate),
+            angular_velocity: true,
+            position: false,
+        }
+    }
+
+    /// The tracker is considered disconnected once the readings end, e.g. when the device
+    /// is unplugged; the motion updates have to be restarted then.
+    fn status(&self) -> ht::Status {
+        self.publisher.status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        Some(self.publisher.subscribe_status())
+    }
+
+    /// The reading thread is not joined, since a silent device would block it indefinitely;
+    /// it exits after the next line.
+    fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
+        if let Some(running) = self.running.lock().unwrap().take() {
+            running.store(false, Ordering::Relaxed);
+        }
+
+        self.publisher.stop();
+
+        Ok(())
+    }
+}
+
+impl Drop for HeadTracker {
+    fn drop(&mut self) {
+        let _ = ht::HeadTracker::stop_motion_updates(self);
+    }
+}
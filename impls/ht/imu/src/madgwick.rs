@@ -0,0 +1,264 @@
+//! # Madgwick AHRS filter
+//!
+//! See S. Madgwick, "An efficient orientation filter for inertial and inertial/magnetic
+//! sensor arrays". The gyroscope is integrated, while a gradient descent step pulls
+//! the orientation towards the one in which the measured gravity (and magnetic north) point
+//! where they should.
+//!
+//! Unlike the paper, the frame is the one of the project: magnetic north is aligned with
+//! the y-axis, so that facing north means facing forward.
+
+use irt_lin_alg::na::{Matrix3, Matrix3x4, Rotation3, Vector3, Vector4};
+use irt_lin_alg::{Orientation, Quaternion};
+
+/// Readings of the sensors, in the frame of the head.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    /// Angular velocity, in radians per second.
+    pub gyro: Vector3<f32>,
+    /// Acceleration, in any units; at rest, points up.
+    pub accel: Vector3<f32>,
+    /// Magnetic field, in any units, if there is a magnetometer.
+    pub mag: Option<Vector3<f32>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Madgwick {
+    /// Gain of the correction, in radians per second: higher values converge faster, but let
+    /// more of the accelerometer noise through. The paper suggests about 0.04.
+    pub beta: f32,
+    orientation: Option<Orientation>,
+}
+
+impl Madgwick {
+    pub fn new(beta: f32) -> Self {
+        Self {
+            beta,
+            orientation: None,
+        }
+    }
+
+    /// The current estimate, if any reading has been processed.
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.orientation
+    }
+
+    pub fn reset(&mut self) {
+        self.orientation = None;
+    }
+
+    /// Process the reading taken `dt` seconds after the previous one.
+    ///
+    /// The first reading initializes the orientation from the accelerometer
+    /// (and the magnetometer) directly, so the estimate doesn't need to converge.
+    pub fn update(&mut self, reading: &Reading, dt: f32) -> Orientation {
+        let orientation = match self.orientation {
+            Some(orientation) => self.step(orientation, reading, dt),
+            None => initial_orientation(reading),
+        };
+
+        self.orientation = Some(orientation);
+
+        orientation
+    }
+
+    fn step(&self, orientation: Orientation, reading: &Reading, dt: f32) -> Orientation {
+        let q = orientation.into_inner();
+        let gyro = Quaternion::from_imag(reading.gyro);
+
+        let mut rate = q * gyro * 0.5;
+
+        if let Some(gradient) = gradient(&orientation, reading) {
+            let gradient = Quaternion::new(gradient[0], gradient[1], gradient[2], gradient[3]);
+            rate -= gradient * self.beta;
+        }
+
+        Orientation::new_normalize(q + rate * dt)
+    }
+}
+
+/// Normalized gradient of the error between the expected and the measured directions of
+/// gravity and magnetic field; [None] if there's nothing to correct with.
+fn gradient(orientation: &Orientation, reading: &Reading) -> Option<Vector4<f32>> {
+    let q = orientation.quaternion();
+    let accel = reading.accel.try_normalize(f32::EPSILON)?;
+
+    // Gravity points up, along the z-axis
+    let mut gradient = jacobian(q, Axis::Z).transpose() * (expected(q, Axis::Z) - accel);
+
+    if let Some(mag) = reading.mag.and_then(|mag| mag.try_normalize(f32::EPSILON)) {
+        // Reference field: same inclination as measured, but pointing north
+        let field = orientation * mag;
+        let (north, up) = (field.xy().norm(), field.z);
+
+        let expected = expected(q, Axis::Y) * north + expected(q, Axis::Z) * up;
+        let jacobian = jacobian(q, Axis::Y) * north + jacobian(q, Axis::Z) * up;
+
+        gradient += jacobian.transpose() * (expected - mag);
+    }
+
+    gradient.try_normalize(f32::EPSILON)
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    Y,
+    Z,
+}
+
+/// Direction of the world axis, as seen in the frame of the head.
+fn expected(q: &Quaternion, axis: Axis) -> Vector3<f32> {
+    let (w, x, y, z) = (q.w, q.i, q.j, q.k);
+
+    match axis {
+        Axis::Y => Vector3::new(
+            2.0 * (x * y + w * z),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - w * x),
+        ),
+        Axis::Z => Vector3::new(
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            1.0 - 2.0 * (x * x + y * y),
+        ),
+    }
+}
+
+/// Jacobian of [expected] with respect to `(w, x, y, z)`.
+fn jacobian(q: &Quaternion, axis: Axis) -> Matrix3x4<f32> {
+    let (w, x, y, z) = (q.w, q.i, q.j, q.k);
+
+    let m = match axis {
+        Axis::Y => [[z, y, x, w], [0.0, -2.0 * x, 0.0, -2.0 * z], [-x, -w, z, y]],
+        Axis::Z => [[-y, z, -w, x], [x, w, z, y], [0.0, -2.0 * x, -2.0 * y, 0.0]],
+    };
+
+    Matrix3x4::from_fn(|row, column| 2.0 * m[row][column])
+}
+
+/// Orientation in which the measured gravity and field point up and north.
+fn initial_orientation(reading: &Reading) -> Orientation {
+    let Some(up) = reading.accel.try_normalize(f32::EPSILON) else {
+        return Orientation::identity();
+    };
+
+    // Without the magnetometer, the yaw is arbitrary, so keep looking forward
+    let reference = reading.mag.unwrap_or(Vector3::y());
+    let north = (reference - up * reference.dot(&up))
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(|| {
+            // Looking straight up or down
+            let reference = Vector3::z();
+            (reference - up * reference.dot(&up)).normalize()
+        });
+    let east = north.cross(&up);
+
+    // Rows are the world axes in the frame of the head
+    let matrix = Matrix3::from_rows(&[east.transpose(), north.transpose(), up.transpose()]);
+
+    Orientation::from_rotation_matrix(&Rotation3::from_matrix_unchecked(matrix))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+    use irt_lin_alg::YawPitchRoll;
+
+    use super::*;
+
+    const DT: f32 = 0.01;
+
+    /// Magnetic field pointing north and down, as in the northern hemisphere.
+    fn field() -> Vector3<f32> {
+        Vector3::new(0.0, 0.4, -0.9)
+    }
+
+    /// Readings of an ideal sensor in the given orientation, rotating at `rate` about its own axes.
+    fn reading(truth: &Orientation, rate: Vector3<f32>, mag: bool) -> Reading {
+        let inverse = truth.inverse();
+
+        Reading {
+            gyro: rate,
+            accel: inverse * Vector3::z() * 9.81,
+            mag: mag.then(|| inverse * field() * 50.0),
+        }
+    }
+
+    /// Run the filter over 20 s of readings of a still sensor with a gyroscope bias.
+    fn still_with_bias(truth: &Orientation, bias: Vector3<f32>, mag: bool) -> Orientation {
+        let mut filter = Madgwick::new(0.1);
+        let reading = reading(truth, bias, mag);
+
+        for _ in 0..2000 {
+            filter.update(&reading, DT);
+        }
+
+        filter.orientation().unwrap()
+    }
+
+    /// Angle between the estimated and the true directions of gravity.
+    fn tilt_error(estimate: &Orientation, truth: &Orientation) -> f32 {
+        (estimate.inverse() * Vector3::z()).angle(&(truth.inverse() * Vector3::z()))
+    }
+
+    #[test]
+    fn test_initial_orientation() {
+        let truth = YawPitchRoll::new(FRAC_PI_3, 0.3, -0.2).to_orientation();
+
+        let mut filter = Madgwick::new(0.1);
+        let orientation = filter.update(&reading(&truth, Vector3::zeros(), true), DT);
+
+        assert_relative_eq!(orientation, truth, epsilon = 1e-5);
+
+        // Without the magnetometer, only the direction of gravity is known
+        filter.reset();
+        let orientation = filter.update(&reading(&truth, Vector3::zeros(), false), DT);
+
+        assert_relative_eq!(
+            orientation.inverse() * Vector3::z(),
+            truth.inverse() * Vector3::z(),
+            epsilon = 1e-5
+        );
+    }
+
+    #[test]
+    fn test_gyro_integration() {
+        let mut filter = Madgwick::new(0.1);
+        let rate = Vector3::new(0.0, 0.0, 1.0);
+        let mut truth = Orientation::identity();
+
+        // Turning left at 1 rad/s for a second
+        for _ in 0..=100 {
+            filter.update(&reading(&truth, rate, true), DT);
+            truth *= Orientation::from_scaled_axis(rate * DT);
+        }
+
+        let estimate = YawPitchRoll::from(filter.orientation().unwrap());
+
+        assert_relative_eq!(estimate.yaw, YawPitchRoll::from(truth).yaw, epsilon = 0.02);
+        assert_relative_eq!(estimate.pitch, 0.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_tilt_drift_correction() {
+        let truth = YawPitchRoll::new(0.0, 0.2, 0.1).to_orientation();
+        let bias = Vector3::new(0.05, -0.03, 0.0);
+
+        // Uncorrected, the bias would tilt the head by about a radian; the yaw still drifts,
+        // since there's no magnetometer to correct it
+        let estimate = still_with_bias(&truth, bias, false);
+        assert!(tilt_error(&estimate, &truth) < 0.01);
+    }
+
+    #[test]
+    fn test_yaw_drift_correction() {
+        let truth = YawPitchRoll::new(-FRAC_PI_4, 0.0, 0.0).to_orientation();
+        let bias = Vector3::new(0.0, 0.0, 0.05);
+
+        // Only the magnetometer can tell the yaw
+        assert!(still_with_bias(&truth, bias, false).angle_to(&truth) > 0.9);
+        assert!(still_with_bias(&truth, bias, true).angle_to(&truth) < 0.02);
+    }
+}
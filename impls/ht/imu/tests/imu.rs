@@ -0,0 +1,125 @@
+use std::f32::consts::*;
+use std::fmt::Write as _;
+use std::io::Cursor;
+use std::time::Duration;
+
+use approx::assert_relative_eq;
+
+use irt_ht_imu::{Config, GyroUnits, HeadTracker, Source};
+use irt_ht_interface::{ApiError, Error, HeadTracker as _, Status};
+use irt_lin_alg::na::Vector3;
+use irt_lin_alg::{Frame, Orientation, YawPitchRoll};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Lines of an ideal MPU-6050-like sensor, mounted with its y-axis pointing up and z-axis
+/// pointing backward, turning left at 90°/s for a second.
+fn recording() -> String {
+    let mut lines = String::from("MPU6050 connection successful\n");
+    let mut truth = Orientation::identity();
+    let rate = Vector3::new(0.0, 0.0, FRAC_PI_2);
+
+    for _ in 0..100 {
+        let gravity = truth.inverse() * Vector3::z();
+
+        // In the frame of the sensor: gyro in °/s, accel in g
+        let gyro = Frame::IRT.convert_vector(&Frame::RH_Y_UP, &rate.map(f32::to_degrees));
+        let accel = Frame::IRT.convert_vector(&Frame::RH_Y_UP, &gravity);
+
+        writeln!(
+            lines,
+            "{},{},{},{},{},{}",
+            gyro.x, gyro.y, gyro.z, accel.x, accel.y, accel.z
+        )
+        .unwrap();
+
+        truth *= Orientation::from_scaled_axis(rate * 0.01);
+    }
+
+    lines
+}
+
+fn tracker(lines: String) -> HeadTracker {
+    let source: Source = Box::new(move || Ok(Box::new(Cursor::new(lines.clone()))));
+    let config = Config {
+        sample_rate: 100.0,
+        gyro_units: GyroUnits::DegreesPerSecond,
+        frame: Frame::RH_Y_UP,
+        ..Default::default()
+    };
+
+    HeadTracker::with_source(source, config, false)
+}
+
+#[test]
+fn test_fuse_readings() {
+    let tracker = tracker(recording());
+    let stream = tracker.subscribe().unwrap();
+
+    tracker.start_motion_updates().unwrap();
+
+    let samples: Vec<_> = (0..100)
+        .map(|_| stream.recv_timeout(TIMEOUT).unwrap().unwrap())
+        .collect();
+
+    // The status line is skipped
+    assert_eq!(samples[0].sequence, 0);
+    assert_eq!(samples[0].orientation, Orientation::identity());
+
+    let last = samples.last().unwrap();
+    let angles = YawPitchRoll::from(last.orientation);
+
+    assert_relative_eq!(angles.yaw, FRAC_PI_2, epsilon = 0.03);
+    assert_relative_eq!(angles.pitch, 0.0, epsilon = 1e-3);
+    assert_relative_eq!(
+        last.angular_velocity.unwrap(),
+        Vector3::new(0.0, 0.0, FRAC_PI_2),
+        epsilon = 1e-4
+    );
+    assert_eq!(tracker.pull_sample().as_ref(), Some(last));
+
+    tracker.stop_motion_updates().unwrap();
+}
+
+#[test]
+fn test_missing_device() {
+    let tracker = HeadTracker::open("/nonexistent/ttyUSB0", Config::default());
+
+    assert!(matches!(
+        tracker.start_motion_updates(),
+        Err(Error::Api(ApiError::NotAvailable))
+    ));
+}
+
+#[test]
+fn test_paced_file() {
+    let path = std::env::temp_dir().join(format!("irt-ht-imu-{}.txt", std::process::id()));
+    std::fs::write(&path, "0 0 0 0 0 1\n".repeat(5)).unwrap();
+
+    let tracker = HeadTracker::open(&path, Config::default());
+    let stream = tracker.subscribe().unwrap();
+    let statuses = tracker.subscribe_status().unwrap();
+
+    tracker.start_motion_updates().unwrap();
+
+    let first = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+    let mut last = first.clone();
+
+    for _ in 0..4 {
+        last = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+    }
+
+    std::fs::remove_file(&path).unwrap();
+
+    // Read at 100 Hz, rather than all at once
+    assert!(last.timestamp - first.timestamp >= Duration::from_millis(35));
+
+    // The file has ended
+    let statuses: Vec<_> = (0..3)
+        .map(|_| statuses.recv_timeout(TIMEOUT).unwrap().unwrap())
+        .collect();
+    assert_eq!(
+        statuses,
+        [Status::Waiting, Status::Active, Status::Disconnected]
+    );
+}
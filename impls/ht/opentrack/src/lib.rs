@@ -0,0 +1,113 @@
+//! # OpenTrack head-tracking implementation
+//!
+//! [OpenTrack](https://github.com/opentrack/opentrack) supports a wide range of trackers,
+//! from webcams to IMUs, and can forward the head pose over UDP with its
+//! "UDP over network" output. This implementation listens for those packets.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use irt_ht_interface as ht;
+use irt_ht_interface::stream::{SampleStream, StatusStream};
+use irt_ht_udp::UdpReceiver;
+
+pub use packet::{Packet, PACKET_SIZE};
+
+mod packet;
+
+/// Port OpenTrack sends the packets to by default.
+pub const DEFAULT_PORT: u16 = 4242;
+
+/// How long OpenTrack may stay silent before it's considered disconnected.
+const SILENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Head tracker receiving the head pose from OpenTrack.
+pub struct HeadTracker {
+    receiver: UdpReceiver,
+    latest_packet: Arc<Mutex<Option<Packet>>>,
+}
+
+impl HeadTracker {
+    /// Listen on all the interfaces, on the [default port](DEFAULT_PORT).
+    pub fn new() -> Self {
+        Self::with_address(SocketAddr::from(([0, 0, 0, 0], DEFAULT_PORT)))
+    }
+
+    /// Listen on the given address; the socket is bound once the motion updates start.
+    pub fn with_address(address: SocketAddr) -> Self {
+        Self {
+            receiver: UdpReceiver::new(address, "irt-ht-opentrack")
+                .with_silence_timeout(SILENCE_TIMEOUT),
+            latest_packet: Arc::default(),
+        }
+    }
+
+    /// Address the packets are received on, while the motion updates are running.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.receiver.local_address()
+    }
+
+    /// The most recently received packet, as is.
+    pub fn latest_packet(&self) -> Option<Packet> {
+        *self.latest_packet.lock().unwrap()
+    }
+}
+
+impl Default for HeadTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ht::HeadTracker for HeadTracker {
+    fn start_motion_updates(&self) -> Result<(), ht::Error> {
+        if !self.receiver.is_receiving() {
+            // Packet of the previous session is stale
+            *self.latest_packet.lock().unwrap() = None;
+        }
+
+        let latest_packet = self.latest_packet.clone();
+        let mut sequence = 0;
+
+        self.receiver.start(move |datagram| {
+            let packet = Packet::parse(datagram)?;
+            let sample = ht::OrientationSample::new(packet.orientation(), Instant::now(), sequence)
+                .with_location(packet.location());
+
+            sequence += 1;
+            *latest_packet.lock().unwrap() = Some(packet);
+
+            Some(sample)
+        })
+    }
+
+    fn pull_sample(&self) -> Option<ht::OrientationSample> {
+        self.receiver.publisher().latest()
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        Some(self.receiver.publisher().subscribe())
+    }
+
+    fn capabilities(&self) -> ht::Capabilities {
+        ht::Capabilities {
+            position: true,
+            ..Default::default()
+        }
+    }
+
+    /// The tracker is considered disconnected once OpenTrack stops sending packets for
+    /// a second.
+    fn status(&self) -> ht::Status {
+        self.receiver.publisher().status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        Some(self.receiver.publisher().subscribe_status())
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
+        self.receiver.stop()
+    }
+}
@@ -0,0 +1,133 @@
+use irt_lin_alg::{Frame, Orientation, Point3, YawPitchRoll};
+
+/// Size of an OpenTrack UDP packet, in bytes.
+pub const PACKET_SIZE: usize = 6 * size_of::<f64>();
+
+/// Frame of the positions reported by OpenTrack: x-axis points to the right, y-axis points up,
+/// z-axis points backward, away from the screen.
+const OPENTRACK_FRAME: Frame = Frame::RH_Y_UP;
+
+/// Pose as sent by OpenTrack's "UDP over network" output: six little-endian doubles.
+///
+/// The angles follow OpenTrack's conventions, which differ from the ones of
+/// [YawPitchRoll]: positive yaw turns the head to the right, positive pitch tilts it up and
+/// positive roll tilts it to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Packet {
+    /// Position of the head, in centimeters.
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    /// Angles, in degrees.
+    pub yaw: f64,
+    pub pitch: f64,
+    pub roll: f64,
+}
+
+impl Packet {
+    /// Parse a packet, returning [None] if it has the wrong size or contains non-finite values.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != PACKET_SIZE {
+            return None;
+        }
+
+        let mut values = bytes
+            .chunks_exact(size_of::<f64>())
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()));
+
+        let mut next = || values.next().filter(|value| value.is_finite());
+
+        Some(Self {
+            x: next()?,
+            y: next()?,
+            z: next()?,
+            yaw: next()?,
+            pitch: next()?,
+            roll: next()?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; PACKET_SIZE] {
+        let mut bytes = [0; PACKET_SIZE];
+        let values = [self.x, self.y, self.z, self.yaw, self.pitch, self.roll];
+
+        for (chunk, value) in bytes.chunks_exact_mut(size_of::<f64>()).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Orientation of the head, in the frame of the project.
+    pub fn orientation(&self) -> Orientation {
+        YawPitchRoll::new(
+            -self.yaw.to_radians() as f32,
+            self.pitch.to_radians() as f32,
+            self.roll.to_radians() as f32,
+        )
+        .to_orientation()
+    }
+
+    /// Position of the head in meters, in the frame of the project.
+    pub fn location(&self) -> Point3 {
+        let position = Point3::new(self.x as f32, self.y as f32, self.z as f32) / 100.0;
+
+        OPENTRACK_FRAME.convert_point(&Frame::IRT, &position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let packet = Packet {
+            x: 1.5,
+            y: -2.0,
+            z: 30.0,
+            yaw: 45.0,
+            pitch: -10.0,
+            roll: 5.0,
+        };
+
+        assert_eq!(Packet::parse(&packet.to_bytes()), Some(packet));
+        assert_eq!(Packet::parse(&packet.to_bytes()[1..]), None);
+
+        let mut bytes = packet.to_bytes();
+        bytes[40..].copy_from_slice(&f64::NAN.to_le_bytes());
+        assert_eq!(Packet::parse(&bytes), None);
+    }
+
+    #[test]
+    fn test_conversion() {
+        let packet = Packet {
+            x: 10.0,
+            y: 20.0,
+            z: 30.0,
+            yaw: 90.0,
+            ..Default::default()
+        };
+
+        // Leaning back moves the head backward, i.e. along the negative y-axis of the project
+        assert_relative_eq!(packet.location(), Point3::new(0.1, -0.3, 0.2));
+
+        // Turning right makes the head look along the x-axis
+        let forward = packet.orientation() * Point3::new(0.0, 1.0, 0.0);
+        assert_relative_eq!(forward, Point3::new(1.0, 0.0, 0.0), epsilon = 1e-6);
+
+        let looking_up = Packet {
+            pitch: 30.0,
+            ..Default::default()
+        };
+        assert_relative_eq!(
+            YawPitchRoll::from_orientation(&looking_up.orientation()).pitch,
+            FRAC_PI_6,
+            epsilon = 1e-6
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::net::{SocketAddr, UdpSocket};
+use std::time::Duration;
+
+use approx::assert_relative_eq;
+
+use irt_ht_interface::{HeadTracker as _, Status};
+use irt_ht_opentrack::{HeadTracker, Packet};
+use irt_lin_alg::{Point3, YawPitchRoll};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn local_tracker() -> (HeadTracker, UdpSocket) {
+    let tracker = HeadTracker::with_address(SocketAddr::from(([127, 0, 0, 1], 0)));
+    tracker.start_motion_updates().unwrap();
+
+    // Stands in for OpenTrack
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender.connect(tracker.local_address().unwrap()).unwrap();
+
+    (tracker, sender)
+}
+
+#[test]
+fn test_receive_packets() {
+    let (tracker, sender) = local_tracker();
+    let stream = tracker.subscribe().unwrap();
+
+    assert_eq!(tracker.pull_sample(), None);
+
+    let packet = Packet {
+        x: 0.0,
+        y: 5.0,
+        z: -10.0,
+        yaw: -30.0,
+        pitch: 15.0,
+        roll: 0.0,
+    };
+    sender.send(&packet.to_bytes()).unwrap();
+
+    let sample = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+    let angles = YawPitchRoll::from_orientation(&sample.orientation);
+
+    assert_relative_eq!(angles.yaw, 30f32.to_radians(), epsilon = 1e-6);
+    assert_relative_eq!(angles.pitch, 15f32.to_radians(), epsilon = 1e-6);
+    assert_eq!(tracker.pull_sample().as_ref(), Some(&sample));
+    assert_eq!(tracker.latest_packet(), Some(packet));
+    assert_relative_eq!(sample.location.unwrap(), Point3::new(0.0, 0.1, 0.05));
+    assert_eq!(
+        tracker.pull_pose().map(|pose| pose.location),
+        sample.location
+    );
+
+    // Malformed packets are ignored
+    sender.send(&[0; 10]).unwrap();
+    sender
+        .send(
+            &Packet {
+                yaw: 90.0,
+                ..packet
+            }
+            .to_bytes(),
+        )
+        .unwrap();
+
+    let next = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+    assert_eq!(next.sequence, sample.sequence + 1);
+
+    tracker.stop_motion_updates().unwrap();
+    assert_eq!(tracker.local_address(), None);
+}
+
+#[test]
+fn test_restart() {
+    let (tracker, sender) = local_tracker();
+    let stream = tracker.subscribe().unwrap();
+
+    sender.send(&Packet::default().to_bytes()).unwrap();
+    stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+
+    tracker.stop_motion_updates().unwrap();
+    tracker.start_motion_updates().unwrap();
+
+    // The samples of the previous session are gone, and the socket is bound anew
+    assert_eq!(tracker.pull_sample(), None);
+    assert!(tracker.local_address().is_some());
+}
+
+#[test]
+fn test_status() {
+    let tracker = HeadTracker::with_address(SocketAddr::from(([127, 0, 0, 1], 0)));
+    let statuses = tracker.subscribe_status().unwrap();
+
+    assert_eq!(tracker.status(), Status::Stopped);
+    assert!(tracker.capabilities().position);
+
+    tracker.start_motion_updates().unwrap();
+    assert_eq!(statuses.recv_timeout(TIMEOUT), Ok(Some(Status::Waiting)));
+
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender.connect(tracker.local_address().unwrap()).unwrap();
+    sender.send(&Packet::default().to_bytes()).unwrap();
+    assert_eq!(statuses.recv_timeout(TIMEOUT), Ok(Some(Status::Active)));
+
+    // OpenTrack going silent
+    assert_eq!(
+        statuses.recv_timeout(TIMEOUT),
+        Ok(Some(Status::Disconnected))
+    );
+
+    sender.send(&Packet::default().to_bytes()).unwrap();
+    assert_eq!(statuses.recv_timeout(TIMEOUT), Ok(Some(Status::Active)));
+
+    tracker.stop_motion_updates().unwrap();
+    assert_eq!(statuses.try_recv(), Ok(Some(Status::Stopped)));
+}
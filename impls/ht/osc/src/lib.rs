@@ -0,0 +1,176 @@
+//! # OSC head-tracking implementation
+//!
+//! Many head trackers and phone sensor apps send the orientation as
+//! [OSC](https://opensoundcontrol.stanford.edu) messages over UDP. This implementation listens
+//! for the messages at the configured [addresses](Binding), carrying either quaternions or
+//! Euler angles.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use irt_ht_interface as ht;
+use irt_ht_interface::stream::{SampleStream, StatusStream};
+use irt_ht_udp::UdpReceiver;
+use irt_lin_alg::{Frame, Orientation, Quaternion, YawPitchRoll};
+
+pub mod packet;
+
+/// How the arguments of a message describe the orientation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Four numbers, `w x y z`, of a unit quaternion in the given frame.
+    Quaternion(Frame),
+    /// Three numbers, `yaw pitch roll`, in degrees, following the conventions of [YawPitchRoll].
+    YawPitchRoll,
+}
+
+/// Address of the messages carrying the orientation, and their format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub path: String,
+    pub format: Format,
+}
+
+/// Head tracker receiving the orientation as OSC messages.
+pub struct HeadTracker {
+    bindings: Arc<[Binding]>,
+    receiver: UdpReceiver,
+}
+
+impl Binding {
+    pub fn new(path: impl Into<String>, format: Format) -> Self {
+        Self {
+            path: path.into(),
+            format,
+        }
+    }
+
+    /// Bindings of the messages sent to IEM SceneRotator, which many trackers support:
+    /// `/SceneRotator/quaternions` and `/SceneRotator/ypr`.
+    ///
+    /// The quaternions are in the Ambisonics frame, x-axis pointing forward and z-axis pointing
+    /// up, and the angles follow the same conventions as [YawPitchRoll].
+    pub fn scene_rotator() -> Vec<Binding> {
+        vec![
+            Binding::new(
+                "/SceneRotator/quaternions",
+                Format::Quaternion(Frame::RH_Z_UP),
+            ),
+            Binding::new("/SceneRotator/ypr", Format::YawPitchRoll),
+        ]
+    }
+
+    /// Orientation carried by the message, if it's the one this binding describes and it has
+    /// the expected arguments.
+    pub fn orientation(&self, message: &packet::Message) -> Option<Orientation> {
+        if message.path != self.path {
+            return None;
+        }
+
+        let values: Option<Vec<_>> = message
+            .arguments
+            .iter()
+            .map(|argument| argument.as_f32().filter(|value| value.is_finite()))
+            .collect();
+
+        match (self.format, values?.as_slice()) {
+            (Format::Quaternion(frame), &[w, x, y, z]) => {
+                let quaternion = Quaternion::new(w, x, y, z);
+
+                // Zero, or otherwise garbage
+                if quaternion.norm() < 0.5 {
+                    return None;
+                }
+
+                let orientation = Orientation::new_normalize(quaternion);
+
+                Some(frame.convert_orientation(&Frame::IRT, &orientation))
+            }
+            (Format::YawPitchRoll, &[yaw, pitch, roll]) => Some(
+                YawPitchRoll::new(yaw.to_radians(), pitch.to_radians(), roll.to_radians())
+                    .to_orientation(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl HeadTracker {
+    /// Listen on the given address for the messages described by `bindings`; the socket is
+    /// bound once the motion updates start.
+    pub fn new(address: SocketAddr, bindings: Vec<Binding>) -> Self {
+        Self {
+            bindings: bindings.into(),
+            receiver: UdpReceiver::new(address, "irt-ht-osc"),
+        }
+    }
+
+    /// Address the messages are received on, while the motion updates are running.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.receiver.local_address()
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+}
+
+/// Orientations carried by the messages of the packet which match any of the bindings;
+/// malformed packets carry none.
+fn orientations(bytes: &[u8], bindings: &[Binding]) -> Vec<Orientation> {
+    let Ok(messages) = packet::parse(bytes) else {
+        return Vec::new();
+    };
+
+    messages
+        .iter()
+        .filter_map(|message| {
+            bindings
+                .iter()
+                .find_map(|binding| binding.orientation(message))
+        })
+        .collect()
+}
+
+impl ht::HeadTracker for HeadTracker {
+    fn start_motion_updates(&self) -> Result<(), ht::Error> {
+        let bindings = self.bindings.clone();
+        let mut sequence = 0;
+
+        self.receiver.start(move |datagram| {
+            let timestamp = Instant::now();
+
+            orientations(datagram, &bindings)
+                .into_iter()
+                .map(|orientation| {
+                    let sample = ht::OrientationSample::new(orientation, timestamp, sequence);
+                    sequence += 1;
+                    sample
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+
+    fn pull_sample(&self) -> Option<ht::OrientationSample> {
+        self.receiver.publisher().latest()
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        Some(self.receiver.publisher().subscribe())
+    }
+
+    /// Since OSC senders commonly send the orientation only when it changes, the tracker is
+    /// never considered disconnected: it stays active once the first message has arrived.
+    fn status(&self) -> ht::Status {
+        self.receiver.publisher().status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        Some(self.receiver.publisher().subscribe_status())
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
+        self.receiver.stop()
+    }
+}
@@ -0,0 +1,354 @@
+//! # OSC packets
+//!
+//! Minimal implementation of the [OSC 1.0](https://opensoundcontrol.stanford.edu/spec-1_0.html)
+//! encoding: messages with the standard argument types, and bundles, whose time tags are
+//! ignored, since the samples are used as soon as they arrive.
+
+const BUNDLE_TAG: &[u8] = b"#bundle\0";
+
+/// Argument of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Argument {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+    Nil,
+    Infinitum,
+}
+
+/// OSC message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub path: String,
+    pub arguments: Vec<Argument>,
+}
+
+/// Malformed OSC packet.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ParseError {
+    #[error("packet ends unexpectedly")]
+    UnexpectedEnd,
+    #[error("string is not valid UTF-8")]
+    InvalidString,
+    #[error("address pattern must start with '/'")]
+    InvalidPath,
+    #[error("type tag string must start with ','")]
+    InvalidTypeTags,
+    #[error("unsupported argument type '{0}'")]
+    UnsupportedType(char),
+    #[error("bundle element has invalid size {0}")]
+    InvalidElementSize(i32),
+}
+
+impl Argument {
+    /// Value of a numeric argument.
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            Argument::Int(value) => Some(value as f32),
+            Argument::Long(value) => Some(value as f32),
+            Argument::Float(value) => Some(value),
+            Argument::Double(value) => Some(value as f32),
+            _ => None,
+        }
+    }
+
+    fn type_tag(&self) -> u8 {
+        match self {
+            Argument::Int(_) => b'i',
+            Argument::Long(_) => b'h',
+            Argument::Float(_) => b'f',
+            Argument::Double(_) => b'd',
+            Argument::String(_) => b's',
+            Argument::Blob(_) => b'b',
+            Argument::Bool(true) => b'T',
+            Argument::Bool(false) => b'F',
+            Argument::Nil => b'N',
+            Argument::Infinitum => b'I',
+        }
+    }
+}
+
+impl Message {
+    pub fn new(path: impl Into<String>, arguments: Vec<Argument>) -> Self {
+        Self {
+            path: path.into(),
+            arguments,
+        }
+    }
+
+    /// Encode the message as an OSC packet.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        write_string(&mut bytes, self.path.as_bytes());
+
+        let mut tags = vec![b','];
+        tags.extend(self.arguments.iter().map(Argument::type_tag));
+        write_string(&mut bytes, &tags);
+
+        for argument in &self.arguments {
+            match argument {
+                Argument::Int(value) => bytes.extend(value.to_be_bytes()),
+                Argument::Long(value) => bytes.extend(value.to_be_bytes()),
+                Argument::Float(value) => bytes.extend(value.to_be_bytes()),
+                Argument::Double(value) => bytes.extend(value.to_be_bytes()),
+                Argument::String(value) => write_string(&mut bytes, value.as_bytes()),
+                Argument::Blob(value) => {
+                    bytes.extend((value.len() as i32).to_be_bytes());
+                    bytes.extend(value);
+                    pad(&mut bytes);
+                }
+                Argument::Bool(_) | Argument::Nil | Argument::Infinitum => {}
+            }
+        }
+
+        bytes
+    }
+}
+
+/// Parse an OSC packet, returning all the messages it contains, in order.
+pub fn parse(bytes: &[u8]) -> Result<Vec<Message>, ParseError> {
+    let mut messages = Vec::new();
+    parse_into(bytes, &mut messages)?;
+
+    Ok(messages)
+}
+
+fn parse_into(bytes: &[u8], messages: &mut Vec<Message>) -> Result<(), ParseError> {
+    let Some(elements) = bytes.strip_prefix(BUNDLE_TAG) else {
+        messages.push(parse_message(bytes)?);
+        return Ok(());
+    };
+
+    let mut reader = Reader::new(elements);
+
+    // Time tag
+    reader.take(8)?;
+
+    while !reader.rest().is_empty() {
+        let size = reader.i32()?;
+        let element = usize::try_from(size)
+            .ok()
+            .filter(|size| size % 4 == 0)
+            .ok_or(ParseError::InvalidElementSize(size))?;
+
+        parse_into(reader.take(element)?, messages)?;
+    }
+
+    Ok(())
+}
+
+fn parse_message(bytes: &[u8]) -> Result<Message, ParseError> {
+    let mut reader = Reader::new(bytes);
+
+    let path = reader.string()?;
+
+    if !path.starts_with('/') {
+        return Err(ParseError::InvalidPath);
+    }
+
+    // Some old implementations omit the type tags of messages without arguments
+    if reader.rest().is_empty() {
+        return Ok(Message::new(path, Vec::new()));
+    }
+
+    let tags = reader.string()?;
+    let tags = tags.strip_prefix(',').ok_or(ParseError::InvalidTypeTags)?;
+
+    let arguments = tags
+        .chars()
+        .map(|tag| {
+            Ok(match tag {
+                'i' => Argument::Int(reader.i32()?),
+                'h' => Argument::Long(i64::from_be_bytes(reader.array()?)),
+                'f' => Argument::Float(f32::from_be_bytes(reader.array()?)),
+                'd' => Argument::Double(f64::from_be_bytes(reader.array()?)),
+                's' | 'S' => Argument::String(reader.string()?),
+                'b' => {
+                    let size = reader.i32()?;
+                    let size = usize::try_from(size).map_err(|_| ParseError::UnexpectedEnd)?;
+                    let blob = reader.take(size)?.to_vec();
+                    reader.skip_padding(size)?;
+
+                    Argument::Blob(blob)
+                }
+                'T' => Argument::Bool(true),
+                'F' => Argument::Bool(false),
+                'N' => Argument::Nil,
+                'I' => Argument::Infinitum,
+                tag => return Err(ParseError::UnsupportedType(tag)),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Message::new(path, arguments))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    fn take(&mut self, size: usize) -> Result<&'a [u8], ParseError> {
+        if self.bytes.len() < size {
+            return Err(ParseError::UnexpectedEnd);
+        }
+
+        let (taken, rest) = self.bytes.split_at(size);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn i32(&mut self) -> Result<i32, ParseError> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn skip_padding(&mut self, size: usize) -> Result<(), ParseError> {
+        self.take(padding(size))?;
+        Ok(())
+    }
+
+    /// Null-terminated string, padded to four bytes.
+    fn string(&mut self) -> Result<String, ParseError> {
+        let length = self
+            .bytes
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or(ParseError::UnexpectedEnd)?;
+
+        let string = std::str::from_utf8(self.take(length)?)
+            .map_err(|_| ParseError::InvalidString)?
+            .to_owned();
+
+        // The terminator is a part of the string, as far as the padding is concerned
+        self.take(1)?;
+        self.skip_padding(length + 1)?;
+
+        Ok(string)
+    }
+}
+
+fn padding(size: usize) -> usize {
+    (4 - size % 4) % 4
+}
+
+fn pad(bytes: &mut Vec<u8>) {
+    bytes.resize(bytes.len() + padding(bytes.len()), 0);
+}
+
+fn write_string(bytes: &mut Vec<u8>, string: &[u8]) {
+    bytes.extend(string);
+    bytes.push(0);
+    pad(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_message() {
+        // Example from the specification
+        let bytes = b"/foo\0\0\0\0,iisff\0\0\0\0\x03\xe8\xff\xff\xff\xff\
+                      hello\0\0\0\x3f\x9d\xf3\xb6\x40\xb5\xb2\x2d";
+
+        assert_eq!(
+            parse(bytes).unwrap(),
+            [Message::new(
+                "/foo",
+                vec![
+                    Argument::Int(1000),
+                    Argument::Int(-1),
+                    Argument::String("hello".to_owned()),
+                    Argument::Float(1.234),
+                    Argument::Float(5.678),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let message = Message::new(
+            "/head/quaternion",
+            vec![
+                Argument::Double(0.5),
+                Argument::Long(-7),
+                Argument::Blob(vec![1, 2, 3, 4, 5]),
+                Argument::Bool(true),
+                Argument::String("four".to_owned()),
+                Argument::Nil,
+            ],
+        );
+
+        let bytes = message.to_bytes();
+
+        assert_eq!(bytes.len() % 4, 0);
+        assert_eq!(parse(&bytes).unwrap(), [message]);
+    }
+
+    #[test]
+    fn test_parse_bundle() {
+        let first = Message::new("/a", vec![Argument::Float(1.0)]).to_bytes();
+        let second = Message::new("/b", vec![]).to_bytes();
+
+        let mut inner = BUNDLE_TAG.to_vec();
+        inner.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+        inner.extend((second.len() as i32).to_be_bytes());
+        inner.extend(&second);
+
+        let mut bundle = BUNDLE_TAG.to_vec();
+        bundle.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+
+        for element in [&first, &inner] {
+            bundle.extend((element.len() as i32).to_be_bytes());
+            bundle.extend(element);
+        }
+
+        let paths: Vec<_> = parse(&bundle)
+            .unwrap()
+            .into_iter()
+            .map(|message| message.path)
+            .collect();
+
+        assert_eq!(paths, ["/a", "/b"]);
+    }
+
+    #[test]
+    fn test_malformed() {
+        let message = Message::new("/a", vec![Argument::Int(1)]).to_bytes();
+
+        assert_eq!(
+            parse(&message[..message.len() - 1]),
+            Err(ParseError::UnexpectedEnd)
+        );
+        assert_eq!(parse(b"a\0\0\0"), Err(ParseError::InvalidPath));
+        assert_eq!(parse(b"/a\0\0x\0\0\0"), Err(ParseError::InvalidTypeTags));
+        assert_eq!(
+            parse(b"/a\0\0,c\0\0\0\0\0a"),
+            Err(ParseError::UnsupportedType('c'))
+        );
+
+        let mut bundle = BUNDLE_TAG.to_vec();
+        bundle.extend([0; 8]);
+        bundle.extend((-4i32).to_be_bytes());
+        assert_eq!(parse(&bundle), Err(ParseError::InvalidElementSize(-4)));
+    }
+}
@@ -0,0 +1,113 @@
+use std::f32::consts::*;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::Duration;
+
+use approx::assert_relative_eq;
+
+use irt_ht_interface::{HeadTracker as _, Status};
+use irt_ht_osc::packet::{Argument, Message};
+use irt_ht_osc::{Binding, Format, HeadTracker};
+use irt_lin_alg::na::Vector3;
+use irt_lin_alg::{Frame, Orientation, Point3, YawPitchRoll};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn local_tracker(bindings: Vec<Binding>) -> (HeadTracker, UdpSocket) {
+    let tracker = HeadTracker::new(SocketAddr::from(([127, 0, 0, 1], 0)), bindings);
+    tracker.start_motion_updates().unwrap();
+
+    // Stands in for the OSC head tracker
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender.connect(tracker.local_address().unwrap()).unwrap();
+
+    (tracker, sender)
+}
+
+fn floats(values: &[f32]) -> Vec<Argument> {
+    values.iter().copied().map(Argument::Float).collect()
+}
+
+#[test]
+fn test_scene_rotator_quaternion() {
+    let (tracker, sender) = local_tracker(Binding::scene_rotator());
+    let stream = tracker.subscribe().unwrap();
+    let statuses = tracker.subscribe_status().unwrap();
+
+    assert_eq!(tracker.status(), Status::Waiting);
+
+    // Turning 90° to the left, about the z-axis pointing up
+    let turned = Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2);
+    let q = turned.quaternion();
+
+    sender
+        .send(&Message::new("/SceneRotator/quaternions", floats(&[q.w, q.i, q.j, q.k])).to_bytes())
+        .unwrap();
+
+    let sample = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+    let forward = sample.orientation * Point3::new(0.0, 1.0, 0.0);
+
+    assert_relative_eq!(forward, Point3::new(-1.0, 0.0, 0.0), epsilon = 1e-6);
+    assert_eq!(tracker.pull_sample(), Some(sample));
+    assert_eq!(statuses.recv_timeout(TIMEOUT), Ok(Some(Status::Active)));
+
+    tracker.stop_motion_updates().unwrap();
+    assert_eq!(tracker.status(), Status::Stopped);
+}
+
+#[test]
+fn test_yaw_pitch_roll() {
+    let (tracker, sender) = local_tracker(Binding::scene_rotator());
+    let stream = tracker.subscribe().unwrap();
+
+    // Ints and doubles are accepted as well
+    let arguments = vec![
+        Argument::Int(-45),
+        Argument::Double(10.0),
+        Argument::Float(0.0),
+    ];
+    sender
+        .send(&Message::new("/SceneRotator/ypr", arguments).to_bytes())
+        .unwrap();
+
+    let sample = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+    let angles = YawPitchRoll::from_orientation(&sample.orientation);
+
+    assert_relative_eq!(angles.yaw, -FRAC_PI_4, epsilon = 1e-6);
+    assert_relative_eq!(angles.pitch, 10f32.to_radians(), epsilon = 1e-6);
+}
+
+#[test]
+fn test_custom_binding() {
+    let (tracker, sender) = local_tracker(vec![Binding::new(
+        "/phone/rotation",
+        Format::Quaternion(Frame::IRT),
+    )]);
+    let stream = tracker.subscribe().unwrap();
+
+    let ignored = [
+        // Unknown address
+        Message::new("/SceneRotator/quaternions", floats(&[1.0, 0.0, 0.0, 0.0])).to_bytes(),
+        // Wrong argument count
+        Message::new("/phone/rotation", floats(&[1.0, 0.0, 0.0])).to_bytes(),
+        // Not OSC at all
+        b"hello".to_vec(),
+    ];
+
+    for packet in &ignored {
+        sender.send(packet).unwrap();
+    }
+
+    let rotation = Orientation::from_euler_angles(0.1, 0.2, 0.3);
+    let q = rotation.quaternion();
+    sender
+        .send(&Message::new("/phone/rotation", floats(&[q.w, q.i, q.j, q.k])).to_bytes())
+        .unwrap();
+
+    let sample = stream.recv_timeout(TIMEOUT).unwrap().unwrap();
+
+    assert_eq!(sample.sequence, 0);
+    assert_relative_eq!(sample.orientation, rotation, epsilon = 1e-6);
+
+    tracker.stop_motion_updates().unwrap();
+    assert_eq!(tracker.local_address(), None);
+}
@@ -0,0 +1,475 @@
+//! # Recording format
+//!
+//! Recordings are plain text files, so that they can be inspected and edited by hand.
+//! The first line is the header, [HEADER]; every following line is a sample:
+//!
+//! ```text
+//! # irt-ht-recording 2
+//! # offset     sequence  w        x        y        z        [r ωx ωy ωz] [p x y z]
+//! 0.000000 17 1 0 0 0
+//! 0.010000 18 0.9999875 0 0 0.005 r 0 0 1
+//! 0.020000 19 0.99995 0 0 0.01 r 0 0 1 p 0.01 0 0
+//! ```
+//!
+//! * `offset` is the time elapsed since the first sample, in seconds;
+//! * `sequence` is the sequence number reported by the recorded tracker;
+//! * `w x y z` is the orientation quaternion;
+//! * `r ωx ωy ωz` is the angular velocity, in radians per second, if reported;
+//! * `p x y z` is the location, in meters, if tracked.
+//!
+//! Blank lines and lines starting with `#` are ignored. Recordings of version 1, where
+//! the angular velocity follows the orientation untagged and there are no locations,
+//! are still read.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use irt_ht_interface::{Orientation, OrientationSample, Point3, Quaternion};
+use irt_lin_alg::na::Vector3;
+
+/// Version of the format written by this crate.
+pub const VERSION: u32 = 2;
+
+/// First line of every recording written by this crate.
+pub const HEADER: &str = "# irt-ht-recording 2";
+
+const HEADER_PREFIX: &str = "# irt-ht-recording ";
+
+/// Sample of a recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedSample {
+    /// Time elapsed since the first sample of the recording.
+    pub offset: Duration,
+    pub sequence: u64,
+    pub orientation: Orientation,
+    pub angular_velocity: Option<Vector3<f32>>,
+    pub location: Option<Point3>,
+}
+
+/// Sequence of samples, ordered by time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Recording {
+    samples: Vec<RecordedSample>,
+}
+
+/// Error that might occur when loading a recording from a file.
+#[derive(thiserror::Error, Debug)]
+pub enum LoadError {
+    #[error("failed to read the recording")]
+    Io(#[from] io::Error),
+    #[error("malformed recording")]
+    Parse(#[from] ParseError),
+}
+
+/// Malformed line of a recording.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("line {line}: {problem}")]
+pub struct ParseError {
+    /// Line number, starting from one.
+    pub line: usize,
+    pub problem: Problem,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum Problem {
+    #[error("expected the `{HEADER}` header")]
+    MissingHeader,
+    #[error("unsupported version {0} (expected at most {VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("unexpected number of fields: {0}")]
+    FieldCount(usize),
+    #[error("unknown or repeated field group `{0}`")]
+    InvalidGroup(String),
+    #[error("`{0}` is not a valid number")]
+    InvalidNumber(String),
+    #[error("offset must be finite and non-negative")]
+    InvalidOffset,
+    #[error("orientation is not a unit quaternion")]
+    NotNormalized,
+    #[error("samples must be ordered by offset")]
+    OutOfOrder,
+}
+
+impl RecordedSample {
+    /// Record the sample, taking `start` as the beginning of the recording.
+    pub fn from_sample(sample: &OrientationSample, start: Instant) -> Self {
+        Self {
+            offset: sample.timestamp.saturating_duration_since(start),
+            sequence: sample.sequence,
+            orientation: sample.orientation,
+            angular_velocity: sample.angular_velocity,
+            location: sample.location,
+        }
+    }
+
+    fn parse(line: &str, version: u32) -> Result<Self, Problem> {
+        let fields: Vec<_> = line.split_whitespace().collect();
+
+        if fields.len() < 6 || (version == 1 && fields.len() != 6 && fields.len() != 9) {
+            return Err(Problem::FieldCount(fields.len()));
+        }
+
+        let offset: f64 = parse_number(fields[0])?;
+        let sequence: u64 = parse_number(fields[1])?;
+        let [w, x, y, z] = parse_floats(&fields[2..6])?;
+
+        let offset = Duration::try_from_secs_f64(offset).map_err(|_| Problem::InvalidOffset)?;
+
+        let quaternion = Quaternion::new(w, x, y, z);
+
+        // The values are written with enough precision to round-trip, but may be edited by hand
+        if (quaternion.norm() - 1.0).abs() > 1e-3 {
+            return Err(Problem::NotNormalized);
+        }
+
+        let mut sample = Self {
+            offset,
+            sequence,
+            orientation: Orientation::new_normalize(quaternion),
+            angular_velocity: None,
+            location: None,
+        };
+
+        if version == 1 {
+            if fields.len() == 9 {
+                sample.angular_velocity = Some(Vector3::from(parse_floats(&fields[6..])?));
+            }
+
+            return Ok(sample);
+        }
+
+        sample.parse_groups(&fields[6..])?;
+
+        Ok(sample)
+    }
+
+    /// Parse the optional groups of three values, each tagged by a single letter.
+    fn parse_groups(&mut self, fields: &[&str]) -> Result<(), Problem> {
+        let total = fields.len() + 6;
+
+        for group in fields.chunks(4) {
+            let [tag, values @ ..] = group else {
+                unreachable!("chunks are never empty");
+            };
+
+            let invalid = match *tag {
+                "r" => self.angular_velocity.is_some(),
+                "p" => self.location.is_some(),
+                _ => true,
+            };
+
+            if invalid {
+                return Err(Problem::InvalidGroup(tag.to_string()));
+            }
+
+            if values.len() != 3 {
+                return Err(Problem::FieldCount(total));
+            }
+
+            let values = parse_floats::<3>(values)?;
+
+            if *tag == "r" {
+                self.angular_velocity = Some(Vector3::from(values));
+            } else {
+                self.location = Some(Point3::from(values));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for RecordedSample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let q = self.orientation.quaternion();
+
+        write!(
+            f,
+            "{:.6} {} {} {} {} {}",
+            self.offset.as_secs_f64(),
+            self.sequence,
+            q.w,
+            q.i,
+            q.j,
+            q.k
+        )?;
+
+        if let Some(rate) = self.angular_velocity {
+            write!(f, " r {} {} {}", rate.x, rate.y, rate.z)?;
+        }
+
+        if let Some(location) = self.location {
+            write!(f, " p {} {} {}", location.x, location.y, location.z)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Recording {
+    /// Create a recording from the samples.
+    ///
+    /// # Panics
+    ///
+    /// If the samples are not ordered by offset.
+    pub fn new(samples: Vec<RecordedSample>) -> Self {
+        assert!(
+            samples.is_sorted_by_key(|sample| sample.offset),
+            "samples must be ordered by offset"
+        );
+
+        Self { samples }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        Ok(fs::read_to_string(path)?.parse()?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+
+        self.write_to(&mut file)?;
+
+        file.flush()
+    }
+
+    /// Write the header and all the samples.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+
+        for sample in &self.samples {
+            writeln!(writer, "{sample}")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn samples(&self) -> &[RecordedSample] {
+        &self.samples
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Offset of the last sample.
+    pub fn duration(&self) -> Duration {
+        self.samples
+            .last()
+            .map_or(Duration::ZERO, |sample| sample.offset)
+    }
+}
+
+impl FromStr for Recording {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let Some((line, header)) = lines.next() else {
+            return Err(ParseError {
+                line: 1,
+                problem: Problem::MissingHeader,
+            });
+        };
+
+        let version = parse_header(header).map_err(|problem| ParseError { line, problem })?;
+
+        let mut samples: Vec<RecordedSample> = Vec::new();
+
+        for (line, text) in lines.filter(|(_, line)| !line.starts_with('#')) {
+            let sample = RecordedSample::parse(text, version)
+                .map_err(|problem| ParseError { line, problem })?;
+
+            if samples
+                .last()
+                .is_some_and(|last| last.offset > sample.offset)
+            {
+                return Err(ParseError {
+                    line,
+                    problem: Problem::OutOfOrder,
+                });
+            }
+
+            samples.push(sample);
+        }
+
+        Ok(Self { samples })
+    }
+}
+
+impl FromIterator<RecordedSample> for Recording {
+    fn from_iter<I: IntoIterator<Item = RecordedSample>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Parse the header, returning the version of the recording.
+fn parse_header(header: &str) -> Result<u32, Problem> {
+    let version = header
+        .strip_prefix(HEADER_PREFIX)
+        .ok_or(Problem::MissingHeader)?;
+    let version: u32 = version.trim().parse().map_err(|_| Problem::MissingHeader)?;
+
+    if !(1..=VERSION).contains(&version) {
+        return Err(Problem::UnsupportedVersion(version));
+    }
+
+    Ok(version)
+}
+
+fn parse_number<T: FromStr>(field: &str) -> Result<T, Problem> {
+    field
+        .parse()
+        .map_err(|_| Problem::InvalidNumber(field.to_owned()))
+}
+
+fn parse_floats<const N: usize>(fields: &[&str]) -> Result<[f32; N], Problem> {
+    let mut values = [0.0f32; N];
+
+    for (value, field) in values.iter_mut().zip(fields) {
+        *value = parse_number(field)?;
+
+        if !value.is_finite() {
+            return Err(Problem::InvalidNumber(field.to_string()));
+        }
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn sample(offset_ms: u64, sequence: u64, yaw: f32) -> RecordedSample {
+        RecordedSample {
+            offset: Duration::from_millis(offset_ms),
+            sequence,
+            orientation: Orientation::from_axis_angle(&Vector3::z_axis(), yaw),
+            angular_velocity: None,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut turning = sample(10, 8, FRAC_PI_3);
+        turning.angular_velocity = Some(Vector3::new(0.0, 0.5, -1.25));
+
+        let mut moving = sample(20, 9, -FRAC_PI_3);
+        moving.location = Some(Point3::new(0.1, -0.25, 1.5));
+
+        let mut both = sample(30, 10, FRAC_PI_3);
+        both.angular_velocity = Some(Vector3::z());
+        both.location = Some(Point3::new(0.0, 0.0, 0.01));
+
+        let recording = Recording::new(vec![sample(0, 7, 0.0), turning, moving, both]);
+
+        let mut text = Vec::new();
+        recording.write_to(&mut text).unwrap();
+        let loaded: Recording = String::from_utf8(text).unwrap().parse().unwrap();
+
+        assert_eq!(loaded.samples().len(), 4);
+
+        for (loaded, original) in loaded.samples().iter().zip(recording.samples()) {
+            assert_eq!(loaded.sequence, original.sequence);
+            assert_eq!(loaded.orientation, original.orientation);
+            assert_eq!(loaded.angular_velocity, original.angular_velocity);
+            assert_eq!(loaded.location, original.location);
+            assert_relative_eq!(
+                loaded.offset.as_secs_f64(),
+                original.offset.as_secs_f64(),
+                epsilon = 1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines() {
+        let text = "
+            # irt-ht-recording 1
+            # recorded with AirPods
+
+            0.0 0 1 0 0 0
+            0.5 1 0 0 0 1 0 0 2.5
+        ";
+
+        let recording: Recording = text.parse().unwrap();
+
+        assert_eq!(recording.duration(), Duration::from_millis(500));
+        assert_eq!(
+            recording.samples()[1].angular_velocity,
+            Some(Vector3::new(0.0, 0.0, 2.5))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |text: &str| text.parse::<Recording>().unwrap_err();
+
+        assert_eq!(
+            error("0.0 0 1 0 0 0"),
+            ParseError {
+                line: 1,
+                problem: Problem::MissingHeader
+            }
+        );
+        assert_eq!(
+            error("# irt-ht-recording 3").problem,
+            Problem::UnsupportedVersion(3)
+        );
+        assert_eq!(
+            error(&format!("{HEADER}\n0.0 0 1 0 0")).problem,
+            Problem::FieldCount(5)
+        );
+        assert_eq!(
+            error(&format!("{HEADER}\n0.0 0 1 0 0 0 p 1 2")).problem,
+            Problem::FieldCount(9)
+        );
+        assert_eq!(
+            error(&format!("{HEADER}\n0.0 0 1 0 0 0 0 0 1")).problem,
+            Problem::InvalidGroup("0".to_owned())
+        );
+        assert_eq!(
+            error(&format!("{HEADER}\n0.0 0 1 0 0 0 p 1 2 3 p 1 2 3")).problem,
+            Problem::InvalidGroup("p".to_owned())
+        );
+        assert_eq!(
+            error("# irt-ht-recording 1\n0.0 0 1 0 0 0 p 1 2 3").problem,
+            Problem::FieldCount(10)
+        );
+        assert_eq!(
+            error(&format!("{HEADER}\n0.0 0 1 0 zero 0")).problem,
+            Problem::InvalidNumber("zero".to_owned())
+        );
+        assert_eq!(
+            error(&format!("{HEADER}\n-1.0 0 1 0 0 0")).problem,
+            Problem::InvalidOffset
+        );
+        assert_eq!(
+            error(&format!("{HEADER}\n0.0 0 2 0 0 0")).problem,
+            Problem::NotNormalized
+        );
+
+        let out_of_order = error(&format!("{HEADER}\n0.5 0 1 0 0 0\n\n0.25 1 1 0 0 0"));
+        assert_eq!(out_of_order.line, 4);
+        assert_eq!(out_of_order.problem, Problem::OutOfOrder);
+        assert_eq!(
+            out_of_order.to_string(),
+            "line 4: samples must be ordered by offset"
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! # Head-tracking recording and replay
+//!
+//! Head-tracking bugs are hard to reproduce: they depend on the device, on the platform and on
+//! how exactly the head has moved. This implementation lets one [record](Recorder) the samples
+//! of any head tracker to a file, and [replay](Replay) them later, anywhere.
+//!
+//! See [format] for the file layout.
+
+pub mod format;
+mod recorder;
+mod replay;
+
+pub use format::{RecordedSample, Recording};
+pub use recorder::Recorder;
+pub use replay::{Replay, ReplayOptions};
@@ -0,0 +1,153 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use irt_ht_interface::stream::{SampleStream, StatusStream};
+use irt_ht_interface::{Capabilities, Error, HeadTracker, OrientationSample, Status, UnknownError};
+
+use crate::format::{RecordedSample, HEADER};
+
+/// Head tracker writing every sample of the wrapped one to a recording.
+///
+/// Each sample is recorded once, no matter how many times it's pulled; the samples are
+/// recorded both when pulled and when streamed. Samples older than the last recorded one,
+/// e.g. streamed ones that have been pulled already, are skipped, so that the recording stays
+/// in order.
+///
+/// Recording stops at the first write error, which is reported by [Recorder::flush].
+pub struct Recorder<T> {
+    inner: T,
+    state: Arc<Mutex<RecorderState>>,
+}
+
+struct RecorderState {
+    writer: Option<Box<dyn Write + Send>>,
+    // Timestamp of the first recorded sample
+    start: Option<Instant>,
+    last_sequence: Option<u64>,
+    error: Option<io::Error>,
+}
+
+impl<T: HeadTracker> Recorder<T> {
+    /// Record the samples into `writer`, writing the header right away.
+    pub fn new(inner: T, writer: impl Write + Send + 'static) -> Self {
+        let mut state = RecorderState {
+            writer: Some(Box::new(writer)),
+            start: None,
+            last_sequence: None,
+            error: None,
+        };
+
+        state.write(format_args!("{HEADER}"));
+
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Record the samples into a file, replacing it if it exists.
+    pub fn create(inner: T, path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(inner, BufWriter::new(File::create(path)?)))
+    }
+
+    /// Flush the recorded samples, reporting the error that has stopped the recording, if any.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(error) = state.error.take() {
+            return Err(error);
+        }
+
+        match &mut state.writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl RecorderState {
+    fn record(&mut self, sample: &OrientationSample) {
+        if self
+            .last_sequence
+            .is_some_and(|last| sample.sequence <= last)
+        {
+            return;
+        }
+
+        let start = *self.start.get_or_insert(sample.timestamp);
+        let record = RecordedSample::from_sample(sample, start);
+
+        self.write(format_args!("{record}"));
+        self.last_sequence = Some(sample.sequence);
+    }
+
+    fn write(&mut self, line: fmt::Arguments) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+
+        if let Err(e) = writeln!(writer, "{line}") {
+            self.writer = None;
+            self.error = Some(e);
+        }
+    }
+}
+
+impl<T: HeadTracker> HeadTracker for Recorder<T> {
+    fn start_motion_updates(&self) -> Result<(), Error> {
+        // Sequence numbers start over, while the offsets carry on from the previous session
+        self.state.lock().unwrap().last_sequence = None;
+
+        self.inner.start_motion_updates()
+    }
+
+    fn pull_sample(&self) -> Option<OrientationSample> {
+        let sample = self.inner.pull_sample()?;
+
+        self.state.lock().unwrap().record(&sample);
+
+        Some(sample)
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        let state = self.state.clone();
+
+        self.inner.subscribe().map(|stream| {
+            stream.map_samples(move |sample| {
+                state.lock().unwrap().record(&sample);
+                sample
+            })
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    fn status(&self) -> Status {
+        self.inner.status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        self.inner.subscribe_status()
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+        let result = self.inner.stop_motion_updates();
+
+        if let Some(writer) = &mut self.state.lock().unwrap().writer {
+            // Nothing to be done about it here; the error is reported by the next flush
+            let _ = writer.flush();
+        }
+
+        result
+    }
+}
@@ -0,0 +1,252 @@
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use irt_ht_interface::{Capabilities, Error, HeadTracker, OrientationSample, Status, UnknownError};
+
+use crate::format::{LoadError, Recording};
+
+/// How a recording is played back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOptions {
+    /// Start over once the recording ends, instead of holding the last sample.
+    pub looping: bool,
+    /// Playback speed, where one is real time.
+    pub speed: f32,
+}
+
+/// Head tracker playing a recording back in real time.
+///
+/// The playback starts with the motion updates. The sequence numbers are those of the samples
+/// in the recording, increasing further with every loop, and the timestamps match the time
+/// the samples are due.
+pub struct Replay {
+    recording: Recording,
+    options: ReplayOptions,
+    started: Mutex<Option<Instant>>,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            looping: false,
+            speed: 1.0,
+        }
+    }
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self::with_options(recording, ReplayOptions::default())
+    }
+
+    /// # Panics
+    ///
+    /// If the speed is not finite and positive.
+    pub fn with_options(recording: Recording, options: ReplayOptions) -> Self {
+        assert!(
+            options.speed.is_finite() && options.speed > 0.0,
+            "playback speed must be finite and positive"
+        );
+
+        Self {
+            recording,
+            options,
+            started: Mutex::new(None),
+        }
+    }
+
+    pub fn open(path: impl AsRef<Path>, options: ReplayOptions) -> Result<Self, LoadError> {
+        Ok(Self::with_options(Recording::load(path)?, options))
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn options(&self) -> ReplayOptions {
+        self.options
+    }
+
+    /// Sample due `elapsed` after the playback has started at `start`.
+    fn sample_at(&self, start: Instant, elapsed: Duration) -> Option<OrientationSample> {
+        let samples = self.recording.samples();
+        let duration = self.recording.duration();
+        let position = elapsed.mul_f32(self.options.speed);
+
+        let (mut round, position) = if self.options.looping && !duration.is_zero() {
+            let round = position.as_nanos() / duration.as_nanos();
+            (round as u32, position - duration * round as u32)
+        } else {
+            (0, position)
+        };
+
+        let mut index = samples.partition_point(|sample| sample.offset <= position);
+
+        if index == 0 {
+            // Before the first sample: still holding the last one of the previous round, if any
+            if round == 0 {
+                return None;
+            }
+
+            round -= 1;
+            index = samples.len();
+        }
+
+        let index = index - 1;
+        let sample = &samples[index];
+        let due = (duration * round + sample.offset).div_f32(self.options.speed);
+        let sequence = round as u64 * samples.len() as u64 + index as u64;
+
+        let mut replayed = OrientationSample::new(sample.orientation, start + due, sequence);
+        replayed.angular_velocity = sample
+            .angular_velocity
+            .map(|rate| rate * self.options.speed);
+        replayed.location = sample.location;
+
+        Some(replayed)
+    }
+}
+
+impl HeadTracker for Replay {
+    fn start_motion_updates(&self) -> Result<(), Error> {
+        *self.started.lock().unwrap() = Some(Instant::now());
+        Ok(())
+    }
+
+    fn pull_sample(&self) -> Option<OrientationSample> {
+        let start = (*self.started.lock().unwrap())?;
+
+        self.sample_at(start, start.elapsed())
+    }
+
+    /// The sample rate is the average one of the recording; the angular velocity and
+    /// the location are reported if every sample carries them.
+    fn capabilities(&self) -> Capabilities {
+        let samples = self.recording.samples();
+        let duration = self.recording.duration();
+
+        let sample_rate = (!duration.is_zero())
+            .then(|| (samples.len() - 1) as f32 / duration.as_secs_f32() * self.options.speed);
+
+        Capabilities {
+            sample_rate,
+            angular_velocity: !samples.is_empty()
+                && samples
+                    .iter()
+                    .all(|sample| sample.angular_velocity.is_some()),
+            position: !samples.is_empty() && samples.iter().all(|sample| sample.location.is_some()),
+        }
+    }
+
+    fn status(&self) -> Status {
+        match *self.started.lock().unwrap() {
+            Some(_) => Status::Active,
+            None => Status::Stopped,
+        }
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+        *self.started.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use irt_ht_interface::Orientation;
+    use irt_lin_alg::na::Vector3;
+
+    use super::*;
+    use crate::RecordedSample;
+
+    /// Yaw turning by 0.1 rad every 100 ms, for 200 ms.
+    fn recording() -> Recording {
+        (0..3)
+            .map(|i| RecordedSample {
+                offset: Duration::from_millis(100 * i),
+                sequence: 40 + i,
+                orientation: Orientation::from_axis_angle(&Vector3::z_axis(), 0.1 * i as f32),
+                angular_velocity: Some(Vector3::z()),
+                location: None,
+            })
+            .collect()
+    }
+
+    fn replayed(replay: &Replay, start: Instant, elapsed_ms: u64) -> Option<(u64, f32)> {
+        replay
+            .sample_at(start, Duration::from_millis(elapsed_ms))
+            .map(|sample| (sample.sequence, sample.orientation.angle()))
+    }
+
+    #[test]
+    fn test_real_time() {
+        let replay = Replay::new(recording());
+        let start = Instant::now();
+
+        assert_eq!(replayed(&replay, start, 0), Some((0, 0.0)));
+        assert_eq!(replayed(&replay, start, 150), Some((1, 0.1)));
+
+        // The last sample is held once the recording ends
+        let last = replay.sample_at(start, Duration::from_secs(10)).unwrap();
+        assert_eq!(last.sequence, 2);
+        assert_eq!(last.timestamp, start + Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_speed_and_looping() {
+        let replay = Replay::with_options(
+            recording(),
+            ReplayOptions {
+                looping: true,
+                speed: 2.0,
+            },
+        );
+        let start = Instant::now();
+
+        assert_eq!(replayed(&replay, start, 60), Some((1, 0.1)));
+
+        // The second round starts 100 ms in, and keeps numbering the samples
+        let sample = replay.sample_at(start, Duration::from_millis(160)).unwrap();
+        assert_eq!(sample.sequence, 4);
+        assert_eq!(sample.timestamp, start + Duration::from_millis(150));
+        assert_eq!(sample.angular_velocity, Some(Vector3::z() * 2.0));
+    }
+
+    #[test]
+    fn test_late_first_sample() {
+        let mut samples = recording().samples().to_vec();
+        samples
+            .iter_mut()
+            .for_each(|sample| sample.offset += Duration::from_millis(50));
+
+        let replay = Replay::new(Recording::new(samples));
+
+        assert_eq!(replayed(&replay, Instant::now(), 10), None);
+        assert!(replay.pull_sample().is_none(), "not started yet");
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let replay = Replay::with_options(
+            recording(),
+            ReplayOptions {
+                looping: false,
+                speed: 2.0,
+            },
+        );
+
+        assert_eq!(
+            replay.capabilities(),
+            Capabilities {
+                sample_rate: Some(20.0),
+                angular_velocity: true,
+                position: false,
+            }
+        );
+
+        assert_eq!(replay.status(), Status::Stopped);
+        replay.start_motion_updates().unwrap();
+        assert_eq!(replay.status(), Status::Active);
+    }
+}
@@ -0,0 +1,128 @@
+use std::f32::consts::*;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use irt_ht_interface::testing::Manual;
+use irt_ht_interface::{HeadTracker, Orientation, OrientationSample, Point3};
+use irt_ht_replay::{Recorder, Recording, Replay};
+use irt_lin_alg::na::Vector3;
+
+/// In-memory file, shared with the recorder.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_record_and_replay() {
+    let buffer = Buffer::default();
+    let recorder = Recorder::new(Manual::default(), buffer.clone());
+    let start = Instant::now();
+
+    recorder.start_motion_updates().unwrap();
+
+    for i in 0..4u32 {
+        let orientation = Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_8 * i as f32);
+        let sample = OrientationSample::new(
+            orientation,
+            start + Duration::from_millis(20) * i,
+            u64::from(i) + 100,
+        )
+        .with_location(Point3::new(0.0, 0.01 * i as f32, 0.0));
+
+        recorder.inner().set(sample);
+
+        // Pulled twice, recorded once
+        recorder.pull_sample().unwrap();
+        recorder.pull_sample().unwrap();
+    }
+
+    recorder.stop_motion_updates().unwrap();
+    recorder.flush().unwrap();
+
+    let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let recording: Recording = text.parse().unwrap();
+
+    assert_eq!(recording.samples().len(), 4);
+    assert_eq!(recording.samples()[0].offset, Duration::ZERO);
+    assert_eq!(recording.samples()[3].sequence, 103);
+    assert_eq!(recording.duration(), Duration::from_millis(60));
+
+    let replay = Replay::new(recording.clone());
+
+    replay.start_motion_updates().unwrap();
+
+    let sample = replay.pull_sample().unwrap();
+    assert_eq!(sample.orientation, recording.samples()[0].orientation);
+    assert_eq!(sample.location, Some(Point3::origin()));
+    assert_eq!(sample.sequence, 0);
+
+    // Once the recording is over, the last sample is held
+    std::thread::sleep(Duration::from_millis(80));
+    assert_eq!(
+        replay.pull_orientation(),
+        Some(recording.samples()[3].orientation)
+    );
+
+    replay.stop_motion_updates().unwrap();
+    assert_eq!(replay.pull_sample(), None);
+}
+
+#[test]
+fn test_record_out_of_order() {
+    let buffer = Buffer::default();
+    let recorder = Recorder::new(Manual::default(), buffer.clone());
+    let stream = recorder.subscribe().unwrap();
+    let start = Instant::now();
+    let sample = |sequence: u64| {
+        OrientationSample::new(
+            Orientation::identity(),
+            start + Duration::from_millis(10) * sequence as u32,
+            sequence,
+        )
+    };
+
+    recorder.start_motion_updates().unwrap();
+
+    // Streamed, then delivered again and pulled after a newer one has been recorded
+    for sequence in [1, 0, 2] {
+        recorder.inner().set(sample(sequence));
+        stream
+            .recv_timeout(Duration::from_secs(1))
+            .unwrap()
+            .unwrap();
+        recorder.pull_sample().unwrap();
+    }
+
+    recorder.stop_motion_updates().unwrap();
+    recorder.flush().unwrap();
+
+    let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let recording: Recording = text.parse().unwrap();
+    let sequences: Vec<_> = recording.samples().iter().map(|s| s.sequence).collect();
+
+    assert_eq!(sequences, [1, 2]);
+}
+
+#[test]
+fn test_save_and_load() {
+    let path = std::env::temp_dir().join(format!("irt-ht-replay-{}.txt", std::process::id()));
+    let recording: Recording = "# irt-ht-recording 1\n0.0 3 1 0 0 0\n0.25 4 0 0 0 1"
+        .parse()
+        .unwrap();
+
+    recording.save(&path).unwrap();
+    let loaded = Recording::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), recording);
+}
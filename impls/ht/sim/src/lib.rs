@@ -0,0 +1,152 @@
+//! # Simulated head-tracking implementation
+//!
+//! Produces synthetic orientations following a [Script] of motions, such as constant rotation,
+//! nodding or a random walk, so that the whole head-tracking pipeline can be exercised
+//! without any sensors, e.g. in demos or on CI machines.
+//!
+//! The samples are taken at a fixed rate, at the times told by a [Clock]; with
+//! a [ManualClock], the simulation is fully reproducible.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use irt_ht_interface as ht;
+use irt_lin_alg::na::Vector3;
+
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use motion::{Keyframe, Motion};
+pub use script::{ParseError, Script, Segment};
+
+mod clock;
+mod motion;
+mod script;
+
+/// Rate the samples are taken at, unless told otherwise.
+pub const DEFAULT_SAMPLE_RATE: f32 = 100.0;
+
+/// Highest rate the samples may be taken at, one per [DERIVATIVE_STEP].
+pub const MAX_SAMPLE_RATE: f32 = 1000.0;
+
+/// Time step the angular velocity is estimated over.
+const DERIVATIVE_STEP: Duration = Duration::from_millis(1);
+
+/// Head tracker producing the orientations of a script.
+///
+/// The script starts playing with the motion updates. The samples are taken at the sample
+/// rate: the sequence numbers count the sampling periods since the start, and the timestamps
+/// are those of the periods.
+pub struct HeadTracker {
+    script: Script,
+    sample_rate: f32,
+    clock: Arc<dyn Clock>,
+    state: Mutex<Option<State>>,
+}
+
+struct State {
+    start: Instant,
+    player: script::Player,
+    latest: Option<ht::OrientationSample>,
+}
+
+impl HeadTracker {
+    /// Play the script in real time.
+    ///
+    /// # Panics
+    ///
+    /// If the sample rate is not positive, or exceeds [MAX_SAMPLE_RATE].
+    pub fn new(script: Script, sample_rate: f32) -> Self {
+        Self::with_clock(script, sample_rate, Arc::new(SystemClock))
+    }
+
+    /// Play the script at the time told by the clock.
+    ///
+    /// # Panics
+    ///
+    /// If the sample rate is not positive, or exceeds [MAX_SAMPLE_RATE].
+    pub fn with_clock(script: Script, sample_rate: f32, clock: Arc<dyn Clock>) -> Self {
+        assert!(
+            sample_rate.is_finite() && sample_rate > 0.0 && sample_rate <= MAX_SAMPLE_RATE,
+            "sample rate must be positive and at most {MAX_SAMPLE_RATE} Hz"
+        );
+
+        Self {
+            script,
+            sample_rate,
+            clock,
+            state: Mutex::new(None),
+        }
+    }
+
+    pub fn script(&self) -> &Script {
+        &self.script
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+}
+
+impl State {
+    fn sample(&mut self, now: Instant, sample_rate: f32) -> ht::OrientationSample {
+        let elapsed = now.saturating_duration_since(self.start);
+        let sequence = (elapsed.as_secs_f64() * f64::from(sample_rate)) as u64;
+
+        if let Some(latest) = self.latest.as_ref().filter(|s| s.sequence == sequence) {
+            return latest.clone();
+        }
+
+        let t = Duration::from_secs_f64(sequence as f64 / f64::from(sample_rate));
+
+        let orientation = self.player.orientation_at(t);
+        let ahead = self.player.orientation_at(t + DERIVATIVE_STEP);
+
+        // Rotation over the step, in the world frame
+        let angular_velocity: Vector3<f32> =
+            (ahead * orientation.inverse()).scaled_axis() / DERIVATIVE_STEP.as_secs_f32();
+
+        let sample = ht::OrientationSample::new(orientation, self.start + t, sequence)
+            .with_angular_velocity(angular_velocity);
+
+        self.latest = Some(sample.clone());
+
+        sample
+    }
+}
+
+impl ht::HeadTracker for HeadTracker {
+    fn start_motion_updates(&self) -> Result<(), ht::Error> {
+        *self.state.lock().unwrap() = Some(State {
+            start: self.clock.now(),
+            player: script::Player::new(self.script.clone()),
+            latest: None,
+        });
+
+        Ok(())
+    }
+
+    fn pull_sample(&self) -> Option<ht::OrientationSample> {
+        let mut state = self.state.lock().unwrap();
+
+        Some(state.as_mut()?.sample(self.clock.now(), self.sample_rate))
+    }
+
+    fn capabilities(&self) -> ht::Capabilities {
+        ht::Capabilities {
+            sample_rate: Some(self.sample_rate),
+            angular_velocity: true,
+            position: false,
+        }
+    }
+
+    fn status(&self) -> ht::Status {
+        match *self.state.lock().unwrap() {
+            Some(_) => ht::Status::Active,
+            None => ht::Status::Stopped,
+        }
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), ht::UnknownError> {
+        *self.state.lock().unwrap() = None;
+        Ok(())
+    }
+}
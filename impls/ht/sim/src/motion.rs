@@ -0,0 +1,317 @@
+use std::f32::consts::{FRAC_PI_4, TAU};
+use std::time::Duration;
+
+use irt_ht_interface::Orientation;
+use irt_lin_alg::YawPitchRoll;
+
+/// Interval between the random steps of [Motion::RandomWalk].
+const WALK_STEP: Duration = Duration::from_millis(10);
+
+/// Pitch [Motion::RandomWalk] keeps within, either way.
+const MAX_WALK_PITCH: f32 = FRAC_PI_4;
+
+/// Pattern of head motion, relative to the orientation the head is in when it starts.
+///
+/// The angles follow the conventions of [YawPitchRoll]: positive yaw turns the head
+/// to the left, positive pitch tilts it up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Motion {
+    /// Holding the head still.
+    Still,
+    /// Turning about the vertical axis at a constant rate, in radians per second.
+    Rotation { rate: f32 },
+    /// Nodding, with the pitch following a sine wave of the given amplitude, in radians,
+    /// and frequency, in Hz.
+    Nodding { amplitude: f32, frequency: f32 },
+    /// Turning by `angle` radians at the end of every `interval`; each turn takes `turn`,
+    /// easing in and out.
+    Steps {
+        angle: f32,
+        interval: Duration,
+        turn: Duration,
+    },
+    /// Yaw and pitch drifting at random, by `rate` radians per square root of a second
+    /// (standard deviation); the same seed yields the same walk.
+    RandomWalk { rate: f32, seed: u64 },
+    /// Path through the keyframes, which are ordered by time; the orientations in between
+    /// are interpolated along the shortest arc, and the last one is held.
+    Keyframes(Vec<Keyframe>),
+}
+
+/// Orientation the head passes through at the given time of [Motion::Keyframes].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    pub time: Duration,
+    pub orientation: Orientation,
+}
+
+/// Evaluates a motion, keeping the state of the random walks.
+pub(crate) struct Animation {
+    motion: Motion,
+    walk: Walk,
+}
+
+/// Random walk, as the points at every [WALK_STEP], linearly interpolated in between.
+struct Walk {
+    random: SplitMix64,
+    // Standard deviation of a single step
+    deviation: f32,
+    // Index of the step the current interval starts at
+    step: u32,
+    from: (f32, f32),
+    to: (f32, f32),
+}
+
+/// Small, fast and good enough generator of pseudo-random numbers.
+struct SplitMix64(u64);
+
+impl Keyframe {
+    pub fn new(time: Duration, orientation: Orientation) -> Self {
+        Self { time, orientation }
+    }
+}
+
+impl Animation {
+    pub fn new(motion: Motion) -> Self {
+        let walk = match motion {
+            Motion::RandomWalk { rate, seed } => Walk::new(rate, seed),
+            _ => Walk::new(0.0, 0),
+        };
+
+        Self { motion, walk }
+    }
+
+    /// Orientation relative to the initial one, `t` after the start.
+    ///
+    /// Random walks are only evaluated forward: `t` is not expected to decrease.
+    pub fn orientation_at(&mut self, t: Duration) -> Orientation {
+        let seconds = t.as_secs_f32();
+
+        match &self.motion {
+            Motion::Still => Orientation::identity(),
+            Motion::Rotation { rate } => YawPitchRoll::new(rate * seconds, 0.0, 0.0).into(),
+            Motion::Nodding {
+                amplitude,
+                frequency,
+            } => {
+                let pitch = amplitude * (TAU * frequency * seconds).sin();
+                YawPitchRoll::new(0.0, pitch, 0.0).into()
+            }
+            Motion::Steps {
+                angle,
+                interval,
+                turn,
+            } => YawPitchRoll::new(angle * steps_at(t, *interval, *turn), 0.0, 0.0).into(),
+            Motion::RandomWalk { .. } => {
+                let (yaw, pitch) = self.walk.at(t);
+                YawPitchRoll::new(yaw, pitch, 0.0).into()
+            }
+            Motion::Keyframes(keyframes) => keyframes_at(keyframes, t),
+        }
+    }
+}
+
+/// Number of the steps taken by the time `t`, fractional while turning.
+fn steps_at(t: Duration, interval: Duration, turn: Duration) -> f32 {
+    if interval.is_zero() {
+        return 0.0;
+    }
+
+    let turn = turn.min(interval);
+    let steps = (t.as_nanos() / interval.as_nanos()) as u32;
+    let within = t - interval * steps;
+
+    // The turn happens at the end of the interval
+    let progress = match (within + turn).checked_sub(interval) {
+        Some(turning) if !turn.is_zero() => turning.as_secs_f32() / turn.as_secs_f32(),
+        _ => 0.0,
+    };
+
+    // Smoothstep, so that the head accelerates and decelerates
+    steps as f32 + progress * progress * (3.0 - 2.0 * progress)
+}
+
+fn keyframes_at(keyframes: &[Keyframe], t: Duration) -> Orientation {
+    let next = keyframes.partition_point(|keyframe| keyframe.time <= t);
+
+    let (from, to) = match (keyframes.get(next.wrapping_sub(1)), keyframes.get(next)) {
+        (Some(from), Some(to)) => (from, to),
+        (Some(keyframe), None) | (None, Some(keyframe)) => return keyframe.orientation,
+        (None, None) => return Orientation::identity(),
+    };
+
+    let progress = (t - from.time).as_secs_f32() / (to.time - from.time).as_secs_f32();
+
+    // Interpolation is only ill-defined for the opposite orientations
+    from.orientation
+        .try_slerp(&to.orientation, progress, f32::EPSILON)
+        .unwrap_or(to.orientation)
+}
+
+impl Walk {
+    fn new(rate: f32, seed: u64) -> Self {
+        let mut walk = Self {
+            random: SplitMix64(seed),
+            deviation: rate * WALK_STEP.as_secs_f32().sqrt(),
+            step: 0,
+            from: (0.0, 0.0),
+            to: (0.0, 0.0),
+        };
+
+        walk.to = walk.next_point();
+        walk
+    }
+
+    /// Yaw and pitch at the time `t`, which may not decrease.
+    fn at(&mut self, t: Duration) -> (f32, f32) {
+        while t >= WALK_STEP * (self.step + 1) {
+            self.step += 1;
+            self.from = self.to;
+            self.to = self.next_point();
+        }
+
+        let progress =
+            (t.as_secs_f32() - (WALK_STEP * self.step).as_secs_f32()) / WALK_STEP.as_secs_f32();
+
+        (
+            lerp(self.from.0, self.to.0, progress),
+            lerp(self.from.1, self.to.1, progress),
+        )
+    }
+
+    fn next_point(&mut self) -> (f32, f32) {
+        let (yaw, pitch) = self.to;
+
+        let yaw = yaw + self.deviation * self.random.normal();
+        let pitch = pitch + self.deviation * self.random.normal();
+
+        // Reflected off the limits, to keep the head from looking straight up or down
+        let pitch = if pitch.abs() > MAX_WALK_PITCH {
+            (2.0 * MAX_WALK_PITCH - pitch.abs()).copysign(pitch)
+        } else {
+            pitch
+        };
+
+        (yaw, pitch)
+    }
+}
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed in `[-1, 1]`.
+    fn uniform(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    /// Approximately normally distributed, with zero mean and unit variance.
+    fn normal(&mut self) -> f32 {
+        // The sum of four uniform values has the variance of 4/3
+        let sum: f32 = (0..4).map(|_| self.uniform()).sum();
+        sum * (3.0f32 / 4.0).sqrt()
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+    use irt_lin_alg::na::Vector3;
+
+    use super::*;
+
+    fn angles(motion: &Motion, t_ms: u64) -> YawPitchRoll {
+        Animation::new(motion.clone())
+            .orientation_at(Duration::from_millis(t_ms))
+            .into()
+    }
+
+    #[test]
+    fn test_rotation_and_nodding() {
+        let rotation = Motion::Rotation { rate: FRAC_PI_4 };
+        assert_relative_eq!(angles(&rotation, 1000).yaw, FRAC_PI_4, epsilon = 1e-6);
+
+        // The rotation axis is the vertical one
+        let turned = Animation::new(rotation).orientation_at(Duration::from_secs(1));
+        assert_relative_eq!(
+            turned.scaled_axis(),
+            Vector3::z() * FRAC_PI_4,
+            epsilon = 1e-6
+        );
+
+        let nodding = Motion::Nodding {
+            amplitude: 0.3,
+            frequency: 0.5,
+        };
+        assert_relative_eq!(angles(&nodding, 500).pitch, 0.3, epsilon = 1e-6);
+        assert_relative_eq!(angles(&nodding, 1500).pitch, -0.3, epsilon = 1e-6);
+        assert_relative_eq!(angles(&nodding, 2000).pitch, 0.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_steps() {
+        let steps = Motion::Steps {
+            angle: FRAC_PI_2,
+            interval: Duration::from_secs(2),
+            turn: Duration::from_millis(500),
+        };
+
+        // Holding, then turning at the end of the interval
+        assert_eq!(angles(&steps, 1000).yaw, 0.0);
+        assert_relative_eq!(angles(&steps, 1750).yaw, FRAC_PI_4, epsilon = 1e-6);
+        assert_relative_eq!(angles(&steps, 2000).yaw, FRAC_PI_2, epsilon = 1e-6);
+        assert_relative_eq!(angles(&steps, 3000).yaw, FRAC_PI_2, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_keyframes() {
+        let keyframes = Motion::Keyframes(vec![
+            Keyframe::new(Duration::from_secs(1), Orientation::identity()),
+            Keyframe::new(
+                Duration::from_secs(3),
+                YawPitchRoll::new(FRAC_PI_2, 0.0, 0.0).into(),
+            ),
+        ]);
+
+        assert_eq!(angles(&keyframes, 0).yaw, 0.0);
+        assert_relative_eq!(angles(&keyframes, 2000).yaw, FRAC_PI_4, epsilon = 1e-6);
+        assert_relative_eq!(angles(&keyframes, 5000).yaw, FRAC_PI_2, epsilon = 1e-6);
+        assert_eq!(angles(&Motion::Keyframes(Vec::new()), 1000).yaw, 0.0);
+    }
+
+    #[test]
+    fn test_random_walk() {
+        let walk = |seed| {
+            let mut animation = Animation::new(Motion::RandomWalk { rate: 1.0, seed });
+
+            (0..1000)
+                .map(|i| animation.orientation_at(Duration::from_millis(i * 25)))
+                .collect::<Vec<_>>()
+        };
+
+        let first = walk(7);
+
+        assert_eq!(first, walk(7));
+        assert_ne!(first, walk(8));
+        assert!(first.iter().all(|orientation| {
+            YawPitchRoll::from(*orientation).pitch.abs() <= MAX_WALK_PITCH + 1e-6
+        }));
+
+        // Continuous, despite the randomness
+        for pair in first.windows(2) {
+            assert!(pair[0].angle_to(&pair[1]) < 0.5);
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use irt_ht_interface::Orientation;
+use irt_lin_alg::YawPitchRoll;
+
+use crate::motion::{Animation, Keyframe, Motion};
+
+/// Motion lasting for a given time, or forever.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub motion: Motion,
+    pub duration: Option<Duration>,
+}
+
+/// Sequence of motions, each picking up from the orientation the previous one has ended at.
+///
+/// Scripts whose segments all end start over once the last one ends, carrying on from
+/// the orientation the head is in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    segments: Vec<Segment>,
+}
+
+/// Malformed script, or one that cannot be played.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ParseError {
+    #[error("script has no segments")]
+    Empty,
+    #[error("only the last segment may last forever")]
+    Unbounded,
+    #[error("looping script must not be empty in time")]
+    ZeroDuration,
+    #[error("unknown motion `{0}`")]
+    UnknownMotion(String),
+    #[error("too many parameters for `{0}`")]
+    TooManyParameters(String),
+    #[error("`{0}` is not a valid number")]
+    InvalidNumber(String),
+    #[error("malformed keyframe `{0}` (expected `seconds=yaw[/pitch[/roll]]`)")]
+    InvalidKeyframe(String),
+    #[error("keyframes must be ordered by time")]
+    KeyframesOutOfOrder,
+}
+
+/// Plays a script, keeping track of the segment in progress.
+pub(crate) struct Player {
+    script: Script,
+    index: usize,
+    // Time since the start of the script the current segment has started at
+    started: Duration,
+    // Orientation the current segment has started from
+    base: Orientation,
+    animation: Animation,
+    last: Duration,
+}
+
+impl Segment {
+    pub fn new(motion: Motion, duration: Duration) -> Self {
+        Self {
+            motion,
+            duration: Some(duration),
+        }
+    }
+
+    pub fn forever(motion: Motion) -> Self {
+        Self {
+            motion,
+            duration: None,
+        }
+    }
+}
+
+impl Script {
+    /// # Panics
+    ///
+    /// If the segments cannot be played: see [ParseError] for the reasons.
+    pub fn new(segments: Vec<Segment>) -> Self {
+        if let Err(e) = validate(&segments) {
+            panic!("invalid script: {e}");
+        }
+
+        Self { segments }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Whether the script starts over once the last segment ends.
+    pub fn is_looping(&self) -> bool {
+        self.segments
+            .iter()
+            .all(|segment| segment.duration.is_some())
+    }
+}
+
+impl From<Motion> for Script {
+    fn from(value: Motion) -> Self {
+        Self::new(vec![Segment::forever(value)])
+    }
+}
+
+/// Parse the text form of a script: segments separated by `;`, each given as
+/// `motion[@seconds]`, where the segments without the duration last forever.
+///
+/// The motions take optional parameters, separated by `:`, in degrees and seconds:
+///
+/// * `still`;
+/// * `rotate[:rate]`, 30°/s by default;
+/// * `nod[:amplitude[:frequency]]`, 15° at 0.5 Hz by default;
+/// * `steps[:angle[:interval[:turn]]]`, 90° every 2 s, turning for 0.3 s, by default;
+/// * `random[:rate[:seed]]`, 20° per square root of a second, with seed 0, by default;
+/// * `keyframes:seconds=yaw/pitch/roll,...`, where the pitch and roll may be omitted.
+///
+/// For example, `rotate:45@4;nod@3;steps:-90@6` keeps turning to the left, nodding and
+/// stepping back to the right.
+impl FromStr for Script {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments = s
+            .split(';')
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty())
+            .map(parse_segment)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        validate(&segments)?;
+
+        Ok(Self { segments })
+    }
+}
+
+impl Player {
+    pub fn new(script: Script) -> Self {
+        let animation = Animation::new(script.segments[0].motion.clone());
+
+        Self {
+            script,
+            index: 0,
+            started: Duration::ZERO,
+            base: Orientation::identity(),
+            animation,
+            last: Duration::ZERO,
+        }
+    }
+
+    /// Orientation `t` after the start of the script.
+    ///
+    /// Going back in time replays the script from the start.
+    pub fn orientation_at(&mut self, t: Duration) -> Orientation {
+        if t < self.last {
+            *self = Self::new(self.script.clone());
+        }
+
+        self.last = t;
+
+        loop {
+            let segment = &self.script.segments[self.index];
+            let elapsed = t - self.started;
+
+            match segment.duration {
+                Some(duration) if elapsed >= duration => {
+                    self.base *= self.animation.orientation_at(duration);
+                    self.started += duration;
+                    self.index = (self.index + 1) % self.script.segments.len();
+
+                    let motion = self.script.segments[self.index].motion.clone();
+                    self.animation = Animation::new(motion);
+                }
+                _ => return self.base * self.animation.orientation_at(elapsed),
+            }
+        }
+    }
+}
+
+fn validate(segments: &[Segment]) -> Result<(), ParseError> {
+    let Some((last, rest)) = segments.split_last() else {
+        return Err(ParseError::Empty);
+    };
+
+    if rest.iter().any(|segment| segment.duration.is_none()) {
+        return Err(ParseError::Unbounded);
+    }
+
+    // Looping through segments that take no time would never get anywhere
+    if last.duration.is_some()
+        && segments
+            .iter()
+            .all(|segment| segment.duration == Some(Duration::ZERO))
+    {
+        return Err(ParseError::ZeroDuration);
+    }
+
+    for segment in segments {
+        if let Motion::Keyframes(keyframes) = &segment.motion {
+            if !keyframes.is_sorted_by_key(|keyframe| keyframe.time) {
+                return Err(ParseError::KeyframesOutOfOrder);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_segment(text: &str) -> Result<Segment, ParseError> {
+    let Some((motion, duration)) = text.split_once('@') else {
+        return Ok(Segment::forever(parse_motion(text)?));
+    };
+
+    Ok(Segment::new(
+        parse_motion(motion.trim())?,
+        parse_duration(duration.trim())?,
+    ))
+}
+
+fn parse_motion(text: &str) -> Result<Motion, ParseError> {
+    let mut fields = text.split(':').map(str::trim);
+    let name = fields.next().unwrap_or_default();
+    let parameters: Vec<_> = fields.collect();
+
+    let expect_at_most = |count: usize| {
+        if parameters.len() > count {
+            return Err(ParseError::TooManyParameters(name.to_owned()));
+        }
+
+        Ok(())
+    };
+
+    let number = |index: usize, default: f32| match parameters.get(index) {
+        Some(field) => parse_number(field),
+        None => Ok(default),
+    };
+
+    let duration = |index: usize, default: Duration| match parameters.get(index) {
+        Some(field) => parse_duration(field),
+        None => Ok(default),
+    };
+
+    let motion = match name {
+        "still" => {
+            expect_at_most(0)?;
+            Motion::Still
+        }
+        "rotate" => {
+            expect_at_most(1)?;
+            Motion::Rotation {
+                rate: number(0, 30.0)?.to_radians(),
+            }
+        }
+        "nod" => {
+            expect_at_most(2)?;
+            Motion::Nodding {
+                amplitude: number(0, 15.0)?.to_radians(),
+                frequency: number(1, 0.5)?,
+            }
+        }
+        "steps" => {
+            expect_at_most(3)?;
+            Motion::Steps {
+                angle: number(0, 90.0)?.to_radians(),
+                interval: duration(1, Duration::from_secs(2))?,
+                turn: duration(2, Duration::from_millis(300))?,
+            }
+        }
+        "random" => {
+            expect_at_most(2)?;
+
+            let seed = match parameters.get(1) {
+                Some(field) => field
+                    .parse()
+                    .map_err(|_| ParseError::InvalidNumber(field.to_string()))?,
+                None => 0,
+            };
+
+            Motion::RandomWalk {
+                rate: number(0, 20.0)?.to_radians(),
+                seed,
+            }
+        }
+        "keyframes" => {
+            expect_at_most(1)?;
+
+            let keyframes = parameters
+                .first()
+                .map_or(Ok(Vec::new()), |list| parse_keyframes(list))?;
+
+            Motion::Keyframes(keyframes)
+        }
+        _ => return Err(ParseError::UnknownMotion(name.to_owned())),
+    };
+
+    Ok(motion)
+}
+
+fn parse_keyframes(list: &str) -> Result<Vec<Keyframe>, ParseError> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|keyframe| !keyframe.is_empty())
+        .map(|keyframe| {
+            let invalid = || ParseError::InvalidKeyframe(keyframe.to_owned());
+
+            let (time, angles) = keyframe.split_once('=').ok_or_else(invalid)?;
+            let angles = angles
+                .split('/')
+                .map(|angle| parse_number(angle.trim()).map(f32::to_radians))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let &[yaw, ref rest @ ..] = angles.as_slice() else {
+                return Err(invalid());
+            };
+
+            if rest.len() > 2 {
+                return Err(invalid());
+            }
+
+            let pitch = rest.first().copied().unwrap_or_default();
+            let roll = rest.get(1).copied().unwrap_or_default();
+
+            Ok(Keyframe::new(
+                parse_duration(time.trim())?,
+                YawPitchRoll::new(yaw, pitch, roll).into(),
+            ))
+        })
+        .collect()
+}
+
+fn parse_number(field: &str) -> Result<f32, ParseError> {
+    field
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| ParseError::InvalidNumber(field.to_owned()))
+}
+
+fn parse_duration(field: &str) -> Result<Duration, ParseError> {
+    Duration::try_from_secs_f32(parse_number(field)?)
+        .map_err(|_| ParseError::InvalidNumber(field.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn yaw_at(player: &mut Player, t_ms: u64) -> f32 {
+        YawPitchRoll::from(player.orientation_at(Duration::from_millis(t_ms))).yaw
+    }
+
+    #[test]
+    fn test_parse() {
+        let script: Script = "rotate:45@4; nod:10:2 @ 0.5; keyframes:0=0,1=90/-10/5"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            script.segments()[0],
+            Segment::new(Motion::Rotation { rate: FRAC_PI_4 }, Duration::from_secs(4))
+        );
+        assert_eq!(
+            script.segments()[1].motion,
+            Motion::Nodding {
+                amplitude: 10f32.to_radians(),
+                frequency: 2.0
+            }
+        );
+        assert!(!script.is_looping());
+
+        let Motion::Keyframes(keyframes) = &script.segments()[2].motion else {
+            panic!("expected keyframes");
+        };
+
+        let angles = YawPitchRoll::from(keyframes[1].orientation);
+        assert_eq!(keyframes[1].time, Duration::from_secs(1));
+        assert_relative_eq!(angles.yaw, FRAC_PI_2, epsilon = 1e-5);
+        assert_relative_eq!(angles.pitch, -10f32.to_radians(), epsilon = 1e-5);
+        assert_relative_eq!(angles.roll, 5f32.to_radians(), epsilon = 1e-5);
+
+        assert_eq!(
+            "random".parse::<Script>().unwrap().segments()[0].motion,
+            Motion::RandomWalk {
+                rate: 20f32.to_radians(),
+                seed: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |text: &str| text.parse::<Script>().unwrap_err();
+
+        assert_eq!(error(" ; "), ParseError::Empty);
+        assert_eq!(error("still; rotate"), ParseError::Unbounded);
+        assert_eq!(error("still@0;nod@0"), ParseError::ZeroDuration);
+        assert_eq!(error("spin"), ParseError::UnknownMotion("spin".to_owned()));
+        assert_eq!(
+            error("still:1"),
+            ParseError::TooManyParameters("still".to_owned())
+        );
+        assert_eq!(
+            error("rotate:fast"),
+            ParseError::InvalidNumber("fast".to_owned())
+        );
+        assert_eq!(
+            error("still@-1"),
+            ParseError::InvalidNumber("-1".to_owned())
+        );
+        assert_eq!(
+            error("keyframes:1=0/0/0/0"),
+            ParseError::InvalidKeyframe("1=0/0/0/0".to_owned())
+        );
+        assert_eq!(error("keyframes:2=0,1=90"), ParseError::KeyframesOutOfOrder);
+    }
+
+    #[test]
+    fn test_segments_pick_up_where_previous_ended() {
+        let mut player = Player::new("rotate:90@1;still@1;rotate:-45@1".parse().unwrap());
+
+        assert_relative_eq!(yaw_at(&mut player, 500), FRAC_PI_4, epsilon = 1e-5);
+        assert_relative_eq!(yaw_at(&mut player, 1500), FRAC_PI_2, epsilon = 1e-5);
+        assert_relative_eq!(yaw_at(&mut player, 3000), FRAC_PI_4, epsilon = 1e-5);
+
+        // Looping, carrying on from the end of the last segment
+        assert_relative_eq!(yaw_at(&mut player, 3500), FRAC_PI_2, epsilon = 1e-5);
+
+        // Rewinding
+        assert_relative_eq!(yaw_at(&mut player, 500), FRAC_PI_4, epsilon = 1e-5);
+    }
+}
@@ -0,0 +1,91 @@
+use std::f32::consts::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+use approx::assert_relative_eq;
+
+use irt_ht_interface::filter::{Filtered, OneEuro};
+use irt_ht_interface::predict::Predicted;
+use irt_ht_interface::recenter::{RecenterMode, Recentered};
+use irt_ht_interface::{HeadTracker as _, Status};
+use irt_ht_sim::{HeadTracker, ManualClock, Motion, Script};
+use irt_lin_alg::na::Vector3;
+use irt_lin_alg::YawPitchRoll;
+
+fn simulated(script: &str) -> (HeadTracker, Arc<ManualClock>) {
+    let clock = Arc::new(ManualClock::new());
+    let tracker = HeadTracker::with_clock(script.parse().unwrap(), 50.0, clock.clone());
+
+    (tracker, clock)
+}
+
+#[test]
+fn test_samples_follow_clock() {
+    let (tracker, clock) = simulated("rotate:90");
+
+    assert_eq!(tracker.pull_sample(), None);
+    assert_eq!(tracker.status(), Status::Stopped);
+
+    tracker.start_motion_updates().unwrap();
+    let first = tracker.pull_sample().unwrap();
+
+    // Sampled every 20 ms
+    clock.advance(Duration::from_millis(1010));
+    let sample = tracker.pull_sample().unwrap();
+
+    assert_eq!(first.sequence, 0);
+    assert_eq!(sample.sequence, 50);
+    assert_eq!(sample.timestamp - first.timestamp, Duration::from_secs(1));
+    assert_eq!(tracker.pull_sample(), Some(sample.clone()));
+
+    let angles = YawPitchRoll::from(sample.orientation);
+    assert_relative_eq!(angles.yaw, FRAC_PI_2, epsilon = 1e-4);
+    assert_relative_eq!(
+        sample.angular_velocity.unwrap(),
+        Vector3::z() * FRAC_PI_2,
+        epsilon = 1e-2
+    );
+
+    assert_eq!(tracker.status(), Status::Active);
+    assert_eq!(tracker.capabilities().sample_rate, Some(50.0));
+
+    // Restarting plays the script from the start
+    tracker.stop_motion_updates().unwrap();
+    assert_eq!(tracker.pull_sample(), None);
+
+    tracker.start_motion_updates().unwrap();
+    let restarted = tracker.pull_sample().unwrap();
+
+    assert_eq!(restarted.sequence, 0);
+    assert_eq!(restarted.orientation, first.orientation);
+}
+
+#[test]
+fn test_full_pipeline() {
+    let clock = Arc::new(ManualClock::new());
+    let script = Script::from(Motion::Rotation { rate: FRAC_PI_4 });
+    let tracker = HeadTracker::with_clock(script, 100.0, clock.clone());
+
+    let tracker = Filtered::new(tracker, OneEuro::default());
+    let tracker = Predicted::new(tracker, Duration::from_millis(100));
+    let tracker = Recentered::new(tracker);
+
+    tracker.start_motion_updates().unwrap();
+
+    for _ in 0..100 {
+        clock.advance(Duration::from_millis(10));
+        tracker.pull_sample().unwrap();
+    }
+
+    // Turned by 45°, and predicted 100 ms ahead, give or take the lag of the filter
+    let angles = YawPitchRoll::from(tracker.pull_orientation().unwrap());
+    assert_relative_eq!(angles.yaw, FRAC_PI_4 * 1.1, epsilon = 0.05);
+
+    assert!(tracker.recenter(RecenterMode::YawOnly));
+    clock.advance(Duration::from_millis(10));
+
+    // Recentered onto the predicted orientation, so turned by 10 ms worth since then
+    let angles = YawPitchRoll::from(tracker.pull_orientation().unwrap());
+    assert_relative_eq!(angles.yaw, FRAC_PI_4 * 0.01, epsilon = 0.002);
+    assert_eq!(tracker.status(), Status::Active);
+}
@@ -0,0 +1,199 @@
+//! # UDP receiving for head-tracking implementations
+//!
+//! Many trackers send the head pose as UDP datagrams. [UdpReceiver] takes care of the socket
+//! and the receiving thread, as well as of the status of the tracker, so that
+//! the implementations only have to turn the datagrams into samples.
+
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use irt_ht_interface as ht;
+use irt_ht_interface::stream::Publisher;
+
+/// How often the receiving thread checks whether it should stop.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Largest datagram accepted, which comfortably fits any head-tracking packet.
+pub const MAX_DATAGRAM_SIZE: usize = 1536;
+
+/// Socket receiving the datagrams on a separate thread, while the motion updates are running.
+///
+/// The samples the datagrams are turned into are published by the [publisher](Self::publisher),
+/// which the implementations may forward the [HeadTracker](ht::HeadTracker) methods to.
+pub struct UdpReceiver {
+    address: SocketAddr,
+    thread_name: String,
+    silence_timeout: Option<Duration>,
+    publisher: Arc<Publisher>,
+    session: Mutex<Option<Session>>,
+}
+
+struct Session {
+    local_address: SocketAddr,
+    running: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl UdpReceiver {
+    /// Receive on the given address, on the thread of the given name; the socket is bound
+    /// once the receiving [starts](Self::start).
+    pub fn new(address: SocketAddr, thread_name: impl Into<String>) -> Self {
+        Self {
+            address,
+            thread_name: thread_name.into(),
+            silence_timeout: None,
+            publisher: Arc::default(),
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Consider the sender [disconnected](ht::Status::Disconnected) once it stops sending
+    /// the samples for `timeout`.
+    ///
+    /// Only meant for the senders that keep sending the samples even if the head is still.
+    pub fn with_silence_timeout(mut self, timeout: Duration) -> Self {
+        self.silence_timeout = Some(timeout);
+        self
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Address the datagrams are received on, while receiving.
+    ///
+    /// Differs from the configured address if the latter has port zero.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.session
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|session| session.local_address)
+    }
+
+    pub fn is_receiving(&self) -> bool {
+        self.session.lock().unwrap().is_some()
+    }
+
+    pub fn publisher(&self) -> &Publisher {
+        &self.publisher
+    }
+
+    /// Bind the socket and start receiving, turning every datagram into the samples with
+    /// `handle`; malformed datagrams are expected to yield none, as if they were lost.
+    ///
+    /// Does nothing if already receiving.
+    pub fn start<F, I>(&self, handle: F) -> Result<(), ht::Error>
+    where
+        F: FnMut(&[u8]) -> I + Send + 'static,
+        I: IntoIterator<Item = ht::OrientationSample>,
+    {
+        let mut session = self.session.lock().unwrap();
+
+        if session.is_some() {
+            return Ok(());
+        }
+
+        let socket = UdpSocket::bind(self.address)
+            .map_err(|e| unknown_error(&format!("failed to bind to {}", self.address), e))?;
+        let local_address = socket
+            .local_addr()
+            .map_err(|e| unknown_error("failed to get socket address", e))?;
+
+        socket
+            .set_read_timeout(Some(STOP_POLL_INTERVAL))
+            .map_err(|e| unknown_error("failed to set socket timeout", e))?;
+
+        self.publisher.start();
+
+        let running = Arc::new(AtomicBool::new(true));
+        let handle = thread::Builder::new()
+            .name(self.thread_name.clone())
+            .spawn({
+                let publisher = self.publisher.clone();
+                let running = running.clone();
+                let silence_timeout = self.silence_timeout;
+
+                move || receive(&socket, &publisher, silence_timeout, &running, handle)
+            })
+            .map_err(|e| {
+                self.publisher.stop();
+                unknown_error("failed to spawn receiving thread", e)
+            })?;
+
+        *session = Some(Session {
+            local_address,
+            running,
+            handle,
+        });
+
+        Ok(())
+    }
+
+    /// Stop receiving and close the socket, waiting for the receiving thread to exit.
+    pub fn stop(&self) -> Result<(), ht::UnknownError> {
+        let Some(session) = self.session.lock().unwrap().take() else {
+            return Ok(());
+        };
+
+        session.running.store(false, Ordering::Relaxed);
+
+        let result = session
+            .handle
+            .join()
+            .map_err(|_| ht::UnknownError::new("receiving thread has panicked".to_owned()));
+
+        self.publisher.stop();
+
+        result
+    }
+}
+
+impl Drop for UdpReceiver {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+fn receive<F, I>(
+    socket: &UdpSocket,
+    publisher: &Publisher,
+    silence_timeout: Option<Duration>,
+    running: &AtomicBool,
+    mut handle: F,
+) where
+    F: FnMut(&[u8]) -> I,
+    I: IntoIterator<Item = ht::OrientationSample>,
+{
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    let mut last_sample = None::<Instant>;
+
+    while running.load(Ordering::Relaxed) {
+        let Ok(size) = socket.recv(&mut buffer) else {
+            // Either a timeout, to check whether to stop, or a transient error, such as
+            // ICMP port unreachable
+            let silent = last_sample
+                .zip(silence_timeout)
+                .is_some_and(|(last, timeout)| last.elapsed() > timeout);
+
+            if silent {
+                publisher.disconnect();
+            }
+
+            continue;
+        };
+
+        for sample in handle(&buffer[..size]) {
+            last_sample = Some(sample.timestamp);
+            publisher.publish(sample);
+        }
+    }
+}
+
+fn unknown_error(context: &str, error: io::Error) -> ht::UnknownError {
+    ht::UnknownError::new(format!("{context}: {error}"))
+}
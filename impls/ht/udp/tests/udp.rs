@@ -0,0 +1,80 @@
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use irt_ht_interface::{OrientationSample, Status, UnitQuaternion};
+use irt_ht_udp::UdpReceiver;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Receiver turning every datagram into as many samples as its first byte says.
+fn local_receiver(receiver: UdpReceiver) -> (UdpReceiver, UdpSocket) {
+    let mut sequence = 0;
+
+    receiver
+        .start(move |datagram: &[u8]| {
+            let count = datagram.first().copied().unwrap_or_default();
+
+            (0..count)
+                .map(|_| {
+                    sequence += 1;
+                    OrientationSample::new(UnitQuaternion::identity(), Instant::now(), sequence)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap();
+
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender.connect(receiver.local_address().unwrap()).unwrap();
+
+    (receiver, sender)
+}
+
+fn address() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 0))
+}
+
+#[test]
+fn test_receive() {
+    let (receiver, sender) = local_receiver(UdpReceiver::new(address(), "irt-ht-udp-test"));
+    let publisher = receiver.publisher();
+    let samples = publisher.subscribe();
+
+    assert_eq!(publisher.status(), Status::Waiting);
+
+    // Malformed datagrams yield no samples
+    sender.send(&[0]).unwrap();
+    sender.send(&[2]).unwrap();
+
+    assert_eq!(samples.recv_timeout(TIMEOUT).unwrap().unwrap().sequence, 1);
+    assert_eq!(samples.recv_timeout(TIMEOUT).unwrap().unwrap().sequence, 2);
+    assert_eq!(publisher.latest().unwrap().sequence, 2);
+    assert_eq!(publisher.status(), Status::Active);
+
+    receiver.stop().unwrap();
+
+    assert_eq!(receiver.local_address(), None);
+    assert_eq!(publisher.status(), Status::Stopped);
+}
+
+#[test]
+fn test_silence_timeout() {
+    let receiver = UdpReceiver::new(address(), "irt-ht-udp-test")
+        .with_silence_timeout(Duration::from_millis(200));
+    let (receiver, sender) = local_receiver(receiver);
+    let statuses = receiver.publisher().subscribe_status();
+
+    // Only once the first sample has arrived
+    std::thread::sleep(Duration::from_millis(400));
+    assert_eq!(receiver.publisher().status(), Status::Waiting);
+
+    sender.send(&[1]).unwrap();
+
+    assert_eq!(statuses.recv_timeout(TIMEOUT), Ok(Some(Status::Active)));
+    assert_eq!(
+        statuses.recv_timeout(TIMEOUT),
+        Ok(Some(Status::Disconnected))
+    );
+
+    sender.send(&[1]).unwrap();
+    assert_eq!(statuses.recv_timeout(TIMEOUT), Ok(Some(Status::Active)));
+}
@@ -24,6 +24,11 @@ pub enum SetupError {
     ),
 }
 
+/// Field holding the gain the renderer element applies, i.e. the effective [Source::gain].
+const DISTANCE_GAIN_FIELD: &str = "distance-gain";
+/// Field holding the authored [Source::distance_gain], only read back by [current_scene].
+const AUTHORED_DISTANCE_GAIN_FIELD: &str = "authored-distance-gain";
+
 trait ToValueArray {
     fn to_value_array(&self) -> gst::Array;
 }
@@ -61,7 +66,8 @@ impl ToValueArray for Scene {
                 .field("x", location.x)
                 .field("y", location.y)
                 .field("z", location.z)
-                .field("distance-gain", source.distance_gain())
+                .field(DISTANCE_GAIN_FIELD, source.gain())
+                .field(AUTHORED_DISTANCE_GAIN_FIELD, source.distance_gain())
                 .build()
                 .to_send_value()
         }))
@@ -93,7 +99,12 @@ pub fn current_scene(renderer: &gst::Element) -> Option<Scene> {
                 s.get::<f32>("z").unwrap(),
             ];
 
-            let distance_gain = s.get::<f32>("distance-gain").unwrap();
+            // Objects not set through [HrtfRenderer] have no attenuation applied, so their
+            // "distance-gain" is the authored one.
+            let distance_gain = s
+                .get::<f32>(AUTHORED_DISTANCE_GAIN_FIELD)
+                .or_else(|_| s.get::<f32>(DISTANCE_GAIN_FIELD))
+                .unwrap();
 
             Source::with_location(coords)
                 .distance_gain(distance_gain)
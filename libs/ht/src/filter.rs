@@ -0,0 +1,519 @@
+//! # Orientation filtering
+//!
+//! Raw sensor output is noisy, and the noise becomes audible as the sound scene wobbles around
+//! the listener. [Filtered] wraps any head tracker and passes its orientations through
+//! an [OrientationFilter]; filtered trackers can be wrapped again to chain the filters.
+//!
+//! All the filters work on the rotation itself (interpolating along the shortest arc), rather
+//! than on the quaternion components separately, and use the timestamps of the samples, so
+//! the result doesn't depend on how often the tracker is pulled.
+
+use std::f32::consts::TAU;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::stream::{SampleStream, StatusStream};
+use crate::{
+    Capabilities, Error, HeadTracker, Orientation, OrientationSample, Status, UnknownError,
+};
+
+/// Filter smoothing a sequence of orientations.
+pub trait OrientationFilter: Send {
+    /// Feed the next sample, returning the filtered orientation.
+    fn filter(&mut self, sample: &OrientationSample) -> Orientation;
+
+    /// Forget all the previous samples.
+    fn reset(&mut self);
+}
+
+/// Exponential smoothing: the output follows the input with the given time constant.
+///
+/// Zero time constant passes the input through unchanged.
+#[derive(Debug, Clone)]
+pub struct Exponential {
+    /// Time it takes the output to cover ~63% of the distance to a new, constant input;
+    /// longer time constants smooth more, but also lag more.
+    pub time_constant: Duration,
+    state: Option<(Orientation, Instant)>,
+}
+
+/// One-Euro filter: an adaptive low-pass filter, which smooths heavily when the head is still
+/// and lets fast motion through with little lag.
+///
+/// See Casiez et al., "1€ Filter: A Simple Speed-based Low-pass Filter for Noisy Input
+/// in Interactive Systems" for the tuning procedure: with the head still, decrease
+/// [min_cutoff] until the jitter is gone; then, while turning quickly, increase [beta] until
+/// the lag is acceptable.
+///
+/// [min_cutoff]: OneEuro::min_cutoff
+/// [beta]: OneEuro::beta
+#[derive(Debug, Clone)]
+pub struct OneEuro {
+    /// Cutoff frequency at rest, in Hz.
+    pub min_cutoff: f32,
+    /// How quickly the cutoff frequency increases with the angular speed, in Hz per rad/s.
+    pub beta: f32,
+    /// Cutoff frequency used to smooth the angular speed estimate, in Hz.
+    pub derivative_cutoff: f32,
+    state: Option<OneEuroState>,
+}
+
+#[derive(Debug, Clone)]
+struct OneEuroState {
+    orientation: Orientation,
+    speed: f32,
+    timestamp: Instant,
+}
+
+/// Simple Kalman filter, treating the orientation error as an isotropic random walk.
+///
+/// If the samples carry the angular velocity, it's used to predict the orientation between
+/// the samples, which reduces the lag considerably.
+#[derive(Debug, Clone)]
+pub struct Kalman {
+    /// Variance of the orientation drift per second, in rad² / s.
+    pub process_noise: f32,
+    /// Variance of the measured orientation, in rad².
+    pub measurement_noise: f32,
+    state: Option<KalmanState>,
+}
+
+#[derive(Debug, Clone)]
+struct KalmanState {
+    orientation: Orientation,
+    variance: f32,
+    sample: OrientationSample,
+}
+
+/// Head tracker passing the orientations of the wrapped one through a filter.
+///
+/// Every sample is filtered once, no matter how many times it's pulled, and the pulled and
+/// streamed samples share the same filter. The locations, if any, are passed through as is.
+pub struct Filtered<T> {
+    inner: T,
+    state: Arc<Mutex<FilterState>>,
+}
+
+struct FilterState {
+    filter: Box<dyn OrientationFilter>,
+    last: Option<OrientationSample>,
+}
+
+impl Exponential {
+    pub fn new(time_constant: Duration) -> Self {
+        Self {
+            time_constant,
+            state: None,
+        }
+    }
+}
+
+impl OrientationFilter for Exponential {
+    fn filter(&mut self, sample: &OrientationSample) -> Orientation {
+        let orientation = match self.state {
+            // Which would otherwise give NaN for the samples with the same timestamp
+            Some(_) if self.time_constant.is_zero() => sample.orientation,
+            Some((previous, timestamp)) => {
+                let dt = elapsed(timestamp, sample.timestamp);
+                let alpha = 1.0 - (-dt / self.time_constant.as_secs_f32()).exp();
+
+                interpolate(&previous, &sample.orientation, alpha)
+            }
+            None => sample.orientation,
+        };
+
+        self.state = Some((orientation, sample.timestamp));
+
+        orientation
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+impl OneEuro {
+    pub fn new(min_cutoff: f32, beta: f32) -> Self {
+        Self {
+            min_cutoff,
+            beta,
+            derivative_cutoff: 1.0,
+            state: None,
+        }
+    }
+}
+
+impl Default for OneEuro {
+    fn default() -> Self {
+        Self::new(1.0, 0.5)
+    }
+}
+
+impl OrientationFilter for OneEuro {
+    fn filter(&mut self, sample: &OrientationSample) -> Orientation {
+        let Some(state) = &self.state else {
+            self.state = Some(OneEuroState {
+                orientation: sample.orientation,
+                speed: 0.0,
+                timestamp: sample.timestamp,
+            });
+
+            return sample.orientation;
+        };
+
+        let dt = elapsed(state.timestamp, sample.timestamp);
+
+        if dt == 0.0 {
+            return state.orientation;
+        }
+
+        let raw_speed = state.orientation.angle_to(&sample.orientation) / dt;
+        let speed = lerp(
+            state.speed,
+            raw_speed,
+            smoothing_factor(self.derivative_cutoff, dt),
+        );
+
+        let cutoff = self.min_cutoff + self.beta * speed;
+        let orientation = interpolate(
+            &state.orientation,
+            &sample.orientation,
+            smoothing_factor(cutoff, dt),
+        );
+
+        self.state = Some(OneEuroState {
+            orientation,
+            speed,
+            timestamp: sample.timestamp,
+        });
+
+        orientation
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+impl Kalman {
+    pub fn new(process_noise: f32, measurement_noise: f32) -> Self {
+        Self {
+            process_noise,
+            measurement_noise,
+            state: None,
+        }
+    }
+}
+
+impl OrientationFilter for Kalman {
+    fn filter(&mut self, sample: &OrientationSample) -> Orientation {
+        let Some(state) = &self.state else {
+            self.state = Some(KalmanState {
+                orientation: sample.orientation,
+                variance: self.measurement_noise,
+                sample: sample.clone(),
+            });
+
+            return sample.orientation;
+        };
+
+        let dt = elapsed(state.sample.timestamp, sample.timestamp);
+
+        // Predict, assuming the head keeps rotating at the last measured rate
+        let predicted = match state.sample.angular_velocity {
+            Some(rate) => Orientation::from_scaled_axis(rate * dt) * state.orientation,
+            None => state.orientation,
+        };
+        let variance = state.variance + self.process_noise * dt;
+
+        // Correct with the measurement
+        let gain = variance / (variance + self.measurement_noise);
+        let orientation = interpolate(&predicted, &sample.orientation, gain);
+
+        self.state = Some(KalmanState {
+            orientation,
+            variance: (1.0 - gain) * variance,
+            sample: sample.clone(),
+        });
+
+        orientation
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+impl<T: HeadTracker> Filtered<T> {
+    pub fn new(inner: T, filter: impl OrientationFilter + 'static) -> Self {
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(FilterState {
+                filter: Box::new(filter),
+                last: None,
+            })),
+        }
+    }
+
+    /// Replace the filter; the new one starts from scratch.
+    pub fn set_filter(&self, filter: impl OrientationFilter + 'static) {
+        let mut state = self.state.lock().unwrap();
+        state.filter = Box::new(filter);
+        state.last = None;
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl FilterState {
+    fn apply(&mut self, mut sample: OrientationSample) -> OrientationSample {
+        if let Some(last) = &self.last {
+            if last.sequence == sample.sequence {
+                return last.clone();
+            }
+        }
+
+        sample.orientation = self.filter.filter(&sample);
+        self.last = Some(sample.clone());
+
+        sample
+    }
+}
+
+impl<T: HeadTracker> HeadTracker for Filtered<T> {
+    fn start_motion_updates(&self) -> Result<(), Error> {
+        // Sequence numbers start over, and the old samples are irrelevant anyway
+        let mut state = self.state.lock().unwrap();
+        state.filter.reset();
+        state.last = None;
+        drop(state);
+
+        self.inner.start_motion_updates()
+    }
+
+    fn pull_sample(&self) -> Option<OrientationSample> {
+        let sample = self.inner.pull_sample()?;
+
+        Some(self.state.lock().unwrap().apply(sample))
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        let state = self.state.clone();
+
+        self.inner
+            .subscribe()
+            .map(|stream| stream.map_samples(move |sample| state.lock().unwrap().apply(sample)))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    fn status(&self) -> Status {
+        self.inner.status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        self.inner.subscribe_status()
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+        self.inner.stop_motion_updates()
+    }
+}
+
+/// Seconds elapsed between the timestamps, which may come out of order.
+fn elapsed(earlier: Instant, later: Instant) -> f32 {
+    later.saturating_duration_since(earlier).as_secs_f32()
+}
+
+/// Interpolation weight of an exponential low-pass filter with the given cutoff frequency,
+/// sampled after `dt` seconds.
+fn smoothing_factor(cutoff: f32, dt: f32) -> f32 {
+    let tau = 1.0 / (TAU * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+fn interpolate(from: &Orientation, to: &Orientation, t: f32) -> Orientation {
+    // Interpolation is only ill-defined for the opposite orientations, i.e. a glitch
+    from.try_slerp(to, t.clamp(0.0, 1.0), f32::EPSILON)
+        .unwrap_or(*to)
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+    use irt_lin_alg::na::Vector3;
+
+    use super::*;
+
+    const RATE: Duration = Duration::from_millis(10);
+
+    /// Recording of a tracker sampled at 100 Hz: the head yaws by `yaw(t)` radians, and the sensor
+    /// adds a deterministic jitter of about 0.02 rad around all the axes.
+    fn recording(len: u64, yaw: impl Fn(f32) -> f32) -> Vec<OrientationSample> {
+        let start = Instant::now();
+
+        (0..len)
+            .map(|i| {
+                let t = i as f32 * RATE.as_secs_f32();
+                let jitter = Vector3::new(
+                    (i as f32 * 2.7).sin(),
+                    (i as f32 * 4.1).cos(),
+                    (i as f32 * 5.3).sin(),
+                ) * 0.02;
+                let orientation = Orientation::from_scaled_axis(jitter)
+                    * Orientation::from_axis_angle(&Vector3::z_axis(), yaw(t));
+
+                OrientationSample::new(orientation, start + RATE * i as u32, i)
+            })
+            .collect()
+    }
+
+    /// Mean angle between the filtered orientations and the noiseless ones, past the warm-up.
+    fn mean_error(
+        filter: &mut dyn OrientationFilter,
+        recording: &[OrientationSample],
+        yaw: impl Fn(f32) -> f32,
+    ) -> f32 {
+        let errors: Vec<_> = recording
+            .iter()
+            .enumerate()
+            .map(|(i, sample)| {
+                let truth = Orientation::from_axis_angle(
+                    &Vector3::z_axis(),
+                    yaw(i as f32 * RATE.as_secs_f32()),
+                );
+                filter.filter(sample).angle_to(&truth)
+            })
+            .skip(recording.len() / 2)
+            .collect();
+
+        errors.iter().sum::<f32>() / errors.len() as f32
+    }
+
+    fn still(_: f32) -> f32 {
+        FRAC_PI_4
+    }
+
+    fn turning(t: f32) -> f32 {
+        t * FRAC_PI_2
+    }
+
+    #[test]
+    fn test_filters_reduce_jitter() {
+        let recording = recording(200, still);
+        let raw = mean_error(&mut Exponential::new(Duration::ZERO), &recording, still);
+
+        let filters: [Box<dyn OrientationFilter>; 3] = [
+            Box::new(Exponential::new(Duration::from_millis(100))),
+            Box::new(OneEuro::default()),
+            Box::new(Kalman::new(0.001, 0.0004)),
+        ];
+
+        for mut filter in filters {
+            let filtered = mean_error(filter.as_mut(), &recording, still);
+            assert!(
+                filtered < raw / 3.0,
+                "{filtered} is not much less than {raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_exponential_zero_time_constant() {
+        let mut recording = recording(3, turning);
+
+        // Repeated timestamp
+        recording[2].timestamp = recording[1].timestamp;
+
+        let mut filter = Exponential::new(Duration::ZERO);
+
+        for sample in &recording {
+            assert_eq!(filter.filter(sample), sample.orientation);
+        }
+    }
+
+    #[test]
+    fn test_one_euro_follows_motion() {
+        let recording = recording(200, turning);
+
+        let exponential = mean_error(
+            &mut Exponential::new(Duration::from_millis(100)),
+            &recording,
+            turning,
+        );
+        let one_euro = mean_error(&mut OneEuro::new(1.0, 5.0), &recording, turning);
+
+        // The exponential filter lags behind by a tenth of a second, the One-Euro one hardly does
+        assert_relative_eq!(exponential, FRAC_PI_2 * 0.1, epsilon = 0.01);
+        assert!(one_euro < exponential / 2.0);
+    }
+
+    #[test]
+    fn test_kalman_uses_angular_velocity() {
+        let recording: Vec<_> = recording(200, turning)
+            .into_iter()
+            .map(|sample| sample.with_angular_velocity(Vector3::z() * FRAC_PI_2))
+            .collect();
+
+        let mut kalman = Kalman::new(0.001, 0.0004);
+        let error = mean_error(&mut kalman, &recording, turning);
+
+        assert!(error < 0.01, "{error}");
+    }
+
+    #[test]
+    fn test_samples_filtered_once() {
+        struct Replay(Mutex<Vec<OrientationSample>>);
+
+        impl HeadTracker for Replay {
+            fn start_motion_updates(&self) -> Result<(), Error> {
+                Ok(())
+            }
+
+            fn pull_sample(&self) -> Option<OrientationSample> {
+                let samples = self.0.lock().unwrap();
+                samples.first().cloned()
+            }
+
+            fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+                Ok(())
+            }
+        }
+
+        let recording = recording(2, turning);
+        let tracker = Filtered::new(
+            Replay(Mutex::new(recording.clone())),
+            Exponential::new(Duration::from_millis(10)),
+        );
+
+        // The first sample passes through unchanged, however many times it's pulled
+        assert_eq!(tracker.pull_orientation(), Some(recording[0].orientation));
+        assert_eq!(tracker.pull_orientation(), Some(recording[0].orientation));
+
+        tracker.inner().0.lock().unwrap().remove(0);
+
+        let expected = recording[0]
+            .orientation
+            .slerp(&recording[1].orientation, 1.0 - (-1.0f32).exp());
+        assert_relative_eq!(
+            tracker.pull_orientation().unwrap(),
+            expected,
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            tracker.pull_orientation().unwrap(),
+            expected,
+            epsilon = 1e-6
+        );
+    }
+}
@@ -4,7 +4,20 @@
 //! Any current or future implementation of head-tracking feature shall comply with the traits
 //! described in this module.
 
-pub use irt_lin_alg::{Orientation, Quaternion, UnitQuaternion};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use irt_lin_alg::na::Vector3;
+pub use irt_lin_alg::{Orientation, Point3, Quaternion, UnitQuaternion};
+
+use stream::{SampleStream, StatusStream};
+
+pub mod filter;
+pub mod predict;
+pub mod recenter;
+pub mod stream;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 /// Unknown, unexpected or otherwise unclassified error.
 ///
@@ -64,6 +77,115 @@ pub enum Error {
     Other(#[from] UnknownError),
 }
 
+/// Orientation of the listener at a specific point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrientationSample {
+    /// Orientation, in the coordinate system described in [HeadTracker::pull_orientation].
+    pub orientation: UnitQuaternion,
+    /// Time the orientation has been captured at by the device, as close as the implementation
+    /// can tell.
+    pub timestamp: Instant,
+    /// Number of the sample, which increases with every new sample captured since the motion
+    /// updates have started; pulling the same sample twice yields the same number.
+    pub sequence: u64,
+    /// Rotation rate of the head about x, y and z axes, in radians per second, if reported
+    /// by the device.
+    pub angular_velocity: Option<Vector3<f32>>,
+    /// Location of the head, in meters, if the device tracks the position as well as
+    /// the orientation.
+    ///
+    /// The location is given in the frame of the project, relative to an origin chosen by
+    /// the device, e.g. the initial location of the head.
+    pub location: Option<Point3>,
+}
+
+/// Location and orientation of the head, as reported by six-degrees-of-freedom trackers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pose {
+    pub location: Point3,
+    pub orientation: UnitQuaternion,
+}
+
+/// Features of the motion data reported by an implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Capabilities {
+    /// Nominal rate of the samples, in Hz, if known.
+    pub sample_rate: Option<f32>,
+    /// Whether the samples carry the [angular velocity](OrientationSample::angular_velocity).
+    pub angular_velocity: bool,
+    /// Whether the samples carry the [location](OrientationSample::location), i.e. whether
+    /// [HeadTracker::pull_pose] is available.
+    pub position: bool,
+}
+
+/// Health of a head tracker, as far as the implementation can tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Status {
+    /// The implementation doesn't report its status.
+    #[default]
+    Unknown,
+    /// The motion updates are not running.
+    Stopped,
+    /// The motion updates are about to start, once the user grants the access to motion data.
+    PermissionPending,
+    /// The motion updates are running, but no motion data has arrived yet, e.g. because
+    /// the device is yet to connect.
+    Waiting,
+    /// The motion data is arriving.
+    Active,
+    /// The device has disconnected or gone silent mid-session; the motion updates resume once
+    /// it's back.
+    Disconnected,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::Unknown => "unknown",
+            Status::Stopped => "stopped",
+            Status::PermissionPending => "waiting for permission",
+            Status::Waiting => "waiting for motion data",
+            Status::Active => "active",
+            Status::Disconnected => "disconnected",
+        })
+    }
+}
+
+impl OrientationSample {
+    pub fn new(orientation: UnitQuaternion, timestamp: Instant, sequence: u64) -> Self {
+        Self {
+            orientation,
+            timestamp,
+            sequence,
+            angular_velocity: None,
+            location: None,
+        }
+    }
+
+    pub fn with_angular_velocity(mut self, angular_velocity: Vector3<f32>) -> Self {
+        self.angular_velocity = Some(angular_velocity);
+        self
+    }
+
+    pub fn with_location(mut self, location: Point3) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Pose of the head, if the sample carries the location.
+    pub fn pose(&self) -> Option<Pose> {
+        self.location.map(|location| Pose {
+            location,
+            orientation: self.orientation,
+        })
+    }
+
+    /// Time elapsed since the sample has been captured.
+    pub fn age(&self) -> Duration {
+        self.timestamp.elapsed()
+    }
+}
+
 /// Head tracking API implementation
 ///
 /// Any current or future implementation shall comply with this trait.
@@ -96,7 +218,63 @@ pub trait HeadTracker {
     /// if the underlying API uses a different coordinate system.
     ///
     /// This can be achieved by swapping coordinates accordingly.
-    fn pull_orientation(&self) -> Option<UnitQuaternion>;
+    fn pull_orientation(&self) -> Option<UnitQuaternion> {
+        self.pull_sample().map(|sample| sample.orientation)
+    }
+
+    /// Pull the latest motion update along with its timing information.
+    ///
+    /// Returns [None] if there is no motion data. The orientation follows the same conventions
+    /// as the one returned by [pull_orientation].
+    ///
+    /// [pull_orientation]: HeadTracker::pull_orientation
+    fn pull_sample(&self) -> Option<OrientationSample>;
+
+    /// Pull the latest pose of the head.
+    ///
+    /// Returns [None] if there is no motion data or the implementation doesn't track
+    /// the location, in which case only [pull_orientation] is available.
+    ///
+    /// [pull_orientation]: HeadTracker::pull_orientation
+    fn pull_pose(&self) -> Option<Pose> {
+        self.pull_sample()?.pose()
+    }
+
+    /// Subscribe to the motion updates, so that every new sample is delivered as soon as
+    /// the device produces it.
+    ///
+    /// Returns [None] if the implementation can only be pulled, in which case the samples may
+    /// still be streamed with [stream::poll] (or [stream::samples], which picks the best option).
+    ///
+    /// The stream delivers the samples while the motion updates are running; see
+    /// [start_motion_updates] and [stop_motion_updates].
+    ///
+    /// [start_motion_updates]: HeadTracker::start_motion_updates
+    /// [stop_motion_updates]: HeadTracker::stop_motion_updates
+    fn subscribe(&self) -> Option<SampleStream> {
+        None
+    }
+
+    /// Features of the motion data the implementation reports.
+    ///
+    /// Defaults to the orientation only, at an unknown rate.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    /// Current status of the implementation.
+    ///
+    /// Returns [Status::Unknown] if the implementation doesn't report it.
+    fn status(&self) -> Status {
+        Status::Unknown
+    }
+
+    /// Subscribe to the status changes, which are delivered as they happen.
+    ///
+    /// Returns [None] if the implementation doesn't report its status.
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        None
+    }
 
     /// Stop receiving motion updates.
     ///
@@ -111,3 +289,41 @@ pub trait HeadTracker {
     /// [start_motion_updates]: HeadTracker::start_motion_updates
     fn stop_motion_updates(&self) -> Result<(), UnknownError>;
 }
+
+impl<T: HeadTracker + ?Sized> HeadTracker for Box<T> {
+    fn start_motion_updates(&self) -> Result<(), Error> {
+        (**self).start_motion_updates()
+    }
+
+    fn pull_orientation(&self) -> Option<UnitQuaternion> {
+        (**self).pull_orientation()
+    }
+
+    fn pull_sample(&self) -> Option<OrientationSample> {
+        (**self).pull_sample()
+    }
+
+    fn pull_pose(&self) -> Option<Pose> {
+        (**self).pull_pose()
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        (**self).subscribe()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        (**self).capabilities()
+    }
+
+    fn status(&self) -> Status {
+        (**self).status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        (**self).subscribe_status()
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+        (**self).stop_motion_updates()
+    }
+}
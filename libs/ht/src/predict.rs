@@ -0,0 +1,260 @@
+//! # Motion prediction
+//!
+//! By the time an orientation is heard, the head has already moved on: the sample has to
+//! reach the scene, and the rendered audio has to make its way through the pipeline.
+//! [Predicted] compensates for that latency by extrapolating the orientation forward, assuming
+//! the head keeps rotating at the same rate.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use irt_lin_alg::na::Vector3;
+
+use crate::stream::{SampleStream, StatusStream};
+use crate::{
+    Capabilities, Error, HeadTracker, Orientation, OrientationSample, Status, UnknownError,
+};
+
+/// Head tracker reporting the orientations the wrapped one is expected to report after
+/// the prediction horizon.
+///
+/// The angular velocity reported by the wrapped tracker is used if available, otherwise it's
+/// estimated from the two most recent samples. Since the estimate amplifies the sensor noise,
+/// such trackers are best [filtered](crate::filter) first. Recentering the wrapped tracker
+/// would look like a sudden turn, so [recentering](crate::recenter) is best applied on top of
+/// the prediction instead.
+///
+/// The timestamps of the samples are left as is; their angular velocity is set to the one
+/// used for the prediction. The locations, if any, are not extrapolated.
+pub struct Predicted<T> {
+    inner: T,
+    state: Arc<Mutex<PredictorState>>,
+}
+
+struct PredictorState {
+    horizon: Duration,
+    // Most recent sample of the wrapped tracker
+    previous: Option<OrientationSample>,
+    // Angular velocity of the most recent sample, reported or estimated
+    rate: Option<Vector3<f32>>,
+}
+
+impl<T: HeadTracker> Predicted<T> {
+    pub fn new(inner: T, horizon: Duration) -> Self {
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(PredictorState {
+                horizon,
+                previous: None,
+                rate: None,
+            })),
+        }
+    }
+
+    pub fn horizon(&self) -> Duration {
+        self.state.lock().unwrap().horizon
+    }
+
+    /// Change how far ahead the orientation is predicted; zero disables the prediction.
+    pub fn set_horizon(&self, horizon: Duration) {
+        self.state.lock().unwrap().horizon = horizon;
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl PredictorState {
+    fn apply(&mut self, sample: OrientationSample) -> OrientationSample {
+        // Pulling the same sample again keeps the rate estimated when it was new
+        let repeated = self
+            .previous
+            .as_ref()
+            .is_some_and(|previous| previous.sequence == sample.sequence);
+
+        if !repeated {
+            self.rate = sample
+                .angular_velocity
+                .or_else(|| self.estimate_angular_velocity(&sample));
+            self.previous = Some(sample.clone());
+        }
+
+        let mut predicted = sample;
+
+        if let Some(rate) = self.rate {
+            let rotation = Orientation::from_scaled_axis(rate * self.horizon.as_secs_f32());
+
+            predicted.orientation = rotation * predicted.orientation;
+            predicted.angular_velocity = Some(rate);
+        }
+
+        predicted
+    }
+
+    fn estimate_angular_velocity(&self, sample: &OrientationSample) -> Option<Vector3<f32>> {
+        let previous = self.previous.as_ref()?;
+        let dt = sample
+            .timestamp
+            .checked_duration_since(previous.timestamp)?
+            .as_secs_f32();
+
+        if dt == 0.0 {
+            return None;
+        }
+
+        let rotation = sample.orientation * previous.orientation.inverse();
+
+        Some(rotation.scaled_axis() / dt)
+    }
+}
+
+impl<T: HeadTracker> HeadTracker for Predicted<T> {
+    fn start_motion_updates(&self) -> Result<(), Error> {
+        // Sequence numbers start over, and the old samples say nothing about the motion
+        let mut state = self.state.lock().unwrap();
+        state.previous = None;
+        state.rate = None;
+        drop(state);
+
+        self.inner.start_motion_updates()
+    }
+
+    fn pull_sample(&self) -> Option<OrientationSample> {
+        let sample = self.inner.pull_sample()?;
+
+        Some(self.state.lock().unwrap().apply(sample))
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        let state = self.state.clone();
+
+        self.inner
+            .subscribe()
+            .map(|stream| stream.map_samples(move |sample| state.lock().unwrap().apply(sample)))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    fn status(&self) -> Status {
+        self.inner.status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        self.inner.subscribe_status()
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+        self.inner.stop_motion_updates()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+    use std::time::Instant;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::testing::Manual;
+
+    fn yaw(angle: f32) -> Orientation {
+        Orientation::from_axis_angle(&Vector3::z_axis(), angle)
+    }
+
+    #[test]
+    fn test_reported_angular_velocity() {
+        let tracker = Predicted::new(Manual::default(), Duration::from_millis(100));
+
+        tracker.inner().set(
+            OrientationSample::new(yaw(FRAC_PI_4), Instant::now(), 0)
+                .with_angular_velocity(Vector3::z() * PI),
+        );
+
+        assert_relative_eq!(
+            tracker.pull_orientation().unwrap(),
+            yaw(FRAC_PI_4 + PI / 10.0),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_estimated_angular_velocity() {
+        let tracker = Predicted::new(Manual::default(), Duration::from_millis(50));
+        let start = Instant::now();
+
+        // Nothing to estimate the rate from yet
+        tracker
+            .inner()
+            .set(OrientationSample::new(yaw(0.0), start, 0));
+        assert_eq!(tracker.pull_orientation(), Some(yaw(0.0)));
+
+        // Turning left at 1 rad/s
+        tracker.inner().set(OrientationSample::new(
+            yaw(0.1),
+            start + Duration::from_millis(100),
+            1,
+        ));
+
+        let sample = tracker.pull_sample().unwrap();
+        assert_relative_eq!(sample.orientation, yaw(0.15), epsilon = 1e-6);
+        assert_relative_eq!(
+            sample.angular_velocity.unwrap(),
+            Vector3::z(),
+            epsilon = 1e-5
+        );
+
+        // Pulling the same sample again doesn't reset the estimate
+        assert_relative_eq!(
+            tracker.pull_orientation().unwrap(),
+            yaw(0.15),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_zero_horizon() {
+        let tracker = Predicted::new(Manual::default(), Duration::from_millis(100));
+
+        tracker.inner().set(
+            OrientationSample::new(yaw(FRAC_PI_4), Instant::now(), 0)
+                .with_angular_velocity(Vector3::z() * PI),
+        );
+        tracker.set_horizon(Duration::ZERO);
+
+        assert_eq!(tracker.horizon(), Duration::ZERO);
+        assert_relative_eq!(
+            tracker.pull_orientation().unwrap(),
+            yaw(FRAC_PI_4),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_horizon_change_keeps_estimate() {
+        let tracker = Predicted::new(Manual::default(), Duration::from_millis(50));
+        let start = Instant::now();
+
+        tracker
+            .inner()
+            .set(OrientationSample::new(yaw(0.0), start, 0));
+        tracker.pull_sample();
+        tracker.inner().set(OrientationSample::new(
+            yaw(0.1),
+            start + Duration::from_millis(100),
+            1,
+        ));
+        tracker.pull_sample();
+
+        // The same sample is predicted further ahead, at the rate estimated already
+        tracker.set_horizon(Duration::from_millis(100));
+        assert_relative_eq!(
+            tracker.pull_orientation().unwrap(),
+            yaw(0.2),
+            epsilon = 1e-6
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! # Recentering
+//!
+//! Head trackers report the orientation relative to whatever reference the device has chosen,
+//! which rarely matches the direction the listener considers to be forward. [Recentered] lets
+//! the listener pick that direction by looking at it and recentering the tracker.
+
+use std::sync::{Arc, Mutex};
+
+use irt_lin_alg::na::Vector3;
+use irt_lin_alg::YawPitchRoll;
+
+use crate::stream::{SampleStream, StatusStream};
+use crate::{
+    Capabilities, Error, HeadTracker, Orientation, OrientationSample, Point3, Status, UnknownError,
+};
+
+/// Part of the orientation captured as the reference when recentering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecenterMode {
+    /// The full orientation becomes the reference, so the current one is reported as identity.
+    #[default]
+    Full,
+    /// Only the yaw becomes the reference, so the listener ends up facing forward, but
+    /// the pitch and roll are still reported relative to the horizon.
+    YawOnly,
+}
+
+/// Head tracker reporting the orientations relative to a reference one.
+///
+/// Until the first recentering, the orientations are reported as is. The locations reported by
+/// six-degrees-of-freedom trackers are recentered as well: the reference location becomes
+/// the origin, and the axes follow the reference orientation.
+pub struct Recentered<T> {
+    inner: T,
+    // Shared with the streams
+    correction: Arc<Mutex<Correction>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Correction {
+    // Inverse of the reference orientation
+    rotation: Orientation,
+    origin: Point3,
+}
+
+impl Default for Correction {
+    fn default() -> Self {
+        Self {
+            rotation: Orientation::identity(),
+            origin: Point3::origin(),
+        }
+    }
+}
+
+impl<T: HeadTracker> Recentered<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            correction: Arc::default(),
+        }
+    }
+
+    /// Make the current orientation (and location, if tracked) the reference one.
+    ///
+    /// Returns `false`, leaving the reference as is, if there is no motion data yet.
+    pub fn recenter(&self, mode: RecenterMode) -> bool {
+        let Some(sample) = self.inner.pull_sample() else {
+            return false;
+        };
+
+        if let Some(location) = sample.location {
+            self.correction.lock().unwrap().origin = location;
+        }
+
+        self.recenter_to(sample.orientation, mode);
+
+        true
+    }
+
+    /// Make the given orientation, as reported by the wrapped tracker, the reference one.
+    pub fn recenter_to(&self, reference: Orientation, mode: RecenterMode) {
+        let reference = match mode {
+            RecenterMode::Full => reference,
+            RecenterMode::YawOnly => {
+                let yaw = YawPitchRoll::from_orientation(&reference).yaw;
+                Orientation::from_axis_angle(&Vector3::z_axis(), yaw)
+            }
+        };
+
+        self.correction.lock().unwrap().rotation = reference.inverse();
+    }
+
+    /// Forget the reference pose, reporting the samples as is again.
+    pub fn reset(&self) {
+        *self.correction.lock().unwrap() = Correction::default();
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+fn corrected(correction: &Mutex<Correction>, mut sample: OrientationSample) -> OrientationSample {
+    let Correction { rotation, origin } = *correction.lock().unwrap();
+
+    sample.orientation = rotation * sample.orientation;
+    sample.angular_velocity = sample.angular_velocity.map(|rate| rotation * rate);
+    sample.location = sample
+        .location
+        .map(|location| Point3::from(rotation * (location - origin)));
+
+    sample
+}
+
+impl<T: HeadTracker> HeadTracker for Recentered<T> {
+    fn start_motion_updates(&self) -> Result<(), Error> {
+        self.inner.start_motion_updates()
+    }
+
+    fn pull_sample(&self) -> Option<OrientationSample> {
+        self.inner
+            .pull_sample()
+            .map(|sample| corrected(&self.correction, sample))
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        let correction = self.correction.clone();
+
+        self.inner
+            .subscribe()
+            .map(|stream| stream.map_samples(move |sample| corrected(&correction, sample)))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    fn status(&self) -> Status {
+        self.inner.status()
+    }
+
+    fn subscribe_status(&self) -> Option<StatusStream> {
+        self.inner.subscribe_status()
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+        self.inner.stop_motion_updates()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+    use std::time::{Duration, Instant};
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::testing::Manual;
+
+    fn orientation(yaw: f32, pitch: f32) -> Orientation {
+        YawPitchRoll::new(yaw, pitch, 0.0).to_orientation()
+    }
+
+    fn sample(orientation: Orientation) -> OrientationSample {
+        OrientationSample::new(orientation, Instant::now(), 0)
+    }
+
+    #[test]
+    fn test_recenter_without_data() {
+        let tracker = Recentered::new(Manual::default());
+
+        assert!(!tracker.recenter(RecenterMode::Full));
+    }
+
+    #[test]
+    fn test_full_recenter() {
+        let tracker = Recentered::new(Manual::default());
+        tracker.inner().set(sample(orientation(FRAC_PI_3, 0.2)));
+
+        assert!(tracker.recenter(RecenterMode::Full));
+        assert_relative_eq!(
+            tracker.pull_orientation().unwrap(),
+            Orientation::identity(),
+            epsilon = 1e-6
+        );
+
+        tracker.recenter_to(orientation(FRAC_PI_3, 0.0), RecenterMode::Full);
+        tracker
+            .inner()
+            .set(sample(orientation(FRAC_PI_3 + 0.5, 0.0)));
+        let angles = YawPitchRoll::from_orientation(&tracker.pull_orientation().unwrap());
+        assert_relative_eq!(angles.yaw, 0.5, epsilon = 1e-5);
+
+        tracker.reset();
+        assert_eq!(
+            tracker.pull_orientation(),
+            Some(orientation(FRAC_PI_3 + 0.5, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_yaw_only_recenter_keeps_pitch() {
+        let tracker = Recentered::new(Manual::default());
+        tracker.inner().set(sample(orientation(-FRAC_PI_2, 0.3)));
+
+        tracker.recenter(RecenterMode::YawOnly);
+        let angles = YawPitchRoll::from_orientation(&tracker.pull_orientation().unwrap());
+
+        assert_relative_eq!(angles.yaw, 0.0, epsilon = 1e-5);
+        assert_relative_eq!(angles.pitch, 0.3, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_recenter_location() {
+        let tracker = Recentered::new(Manual::default());
+        tracker
+            .inner()
+            .set(sample(orientation(FRAC_PI_2, 0.0)).with_location(Point3::new(1.0, 2.0, 0.0)));
+
+        tracker.recenter(RecenterMode::YawOnly);
+        assert_eq!(tracker.pull_pose().unwrap().location, Point3::origin());
+
+        // Leaning towards the direction the listener has been facing, i.e. along the negative
+        // x-axis, is leaning forward after recentering
+        tracker
+            .inner()
+            .set(sample(orientation(FRAC_PI_2, 0.0)).with_location(Point3::new(0.9, 2.0, 0.0)));
+        assert_relative_eq!(
+            tracker.pull_pose().unwrap().location,
+            Point3::new(0.0, 0.1, 0.0),
+            epsilon = 1e-6
+        );
+
+        tracker.reset();
+        assert_eq!(
+            tracker.pull_pose().unwrap().location,
+            Point3::new(0.9, 2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_recentered_stream() {
+        let tracker = Recentered::new(Manual::default());
+        let stream = tracker.subscribe().unwrap();
+
+        tracker.recenter_to(orientation(FRAC_PI_4, 0.0), RecenterMode::Full);
+        tracker.inner().set(sample(orientation(FRAC_PI_4, 0.0)));
+
+        let sample = stream
+            .recv_timeout(Duration::from_secs(1))
+            .unwrap()
+            .unwrap();
+        assert_relative_eq!(sample.orientation, Orientation::identity(), epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,522 @@
+//! # Streaming motion updates
+//!
+//! Besides pulling the latest sample on demand, the motion updates may be consumed as a stream,
+//! which delivers every sample once, at the rate the device produces them.
+//!
+//! Implementations backed by push-based APIs should provide the stream natively, by overriding
+//! [HeadTracker::subscribe]; for all other implementations, [poll] turns pulling into a stream.
+//!
+//! The same machinery delivers the [status](crate::Status) changes of the trackers.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::Duration;
+
+use crate::{HeadTracker, OrientationSample, Status};
+
+/// Stream of the updates produced by a head tracker.
+///
+/// The stream ends (i.e. [recv] returns [None]) once the producer is gone; dropping the stream
+/// unsubscribes from the updates.
+///
+/// [recv]: Stream::recv
+pub struct Stream<T> {
+    receiver: Receiver<T>,
+    // Lets the producers tell if the stream is still alive without sending anything
+    _alive: Arc<()>,
+}
+
+/// Stream of the samples produced by a head tracker.
+pub type SampleStream = Stream<OrientationSample>;
+
+/// Stream of the status changes of a head tracker.
+pub type StatusStream = Stream<Status>;
+
+/// Stream producer side, which delivers the updates to all the subscribed streams.
+///
+/// This is meant to be used by the implementations providing native streams.
+pub struct Subscribers<T = OrientationSample> {
+    senders: Mutex<Vec<Sender<T>>>,
+}
+
+impl<T> Stream<T> {
+    /// Create a stream along with the sender feeding it and the handle telling whether
+    /// the stream is still alive.
+    fn channel() -> (Sender<T>, Weak<()>, Self) {
+        let (sender, receiver) = mpsc::channel();
+        let alive = Arc::new(());
+        let handle = Arc::downgrade(&alive);
+
+        (
+            sender,
+            handle,
+            Self {
+                receiver,
+                _alive: alive,
+            },
+        )
+    }
+
+    /// Block until the next update arrives; returns [None] if the stream has ended.
+    pub fn recv(&self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+
+    /// Wait for the next update for at most `timeout`.
+    ///
+    /// Returns `Ok(None)` on timeout and `Err(StreamEnded)` if the stream has ended.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Option<T>, StreamEnded> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(sample) => Ok(Some(sample)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(StreamEnded),
+        }
+    }
+
+    /// Take the next update if there is one already.
+    ///
+    /// Returns `Ok(None)` if there is none and `Err(StreamEnded)` if the stream has ended.
+    pub fn try_recv(&self) -> Result<Option<T>, StreamEnded> {
+        match self.receiver.try_recv() {
+            Ok(sample) => Ok(Some(sample)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(StreamEnded),
+        }
+    }
+
+    /// Skip all the pending updates but the most recent one, which is returned, if any.
+    pub fn latest(&self) -> Result<Option<T>, StreamEnded> {
+        let mut latest = None;
+
+        loop {
+            match self.try_recv() {
+                Ok(Some(sample)) => latest = Some(sample),
+                Ok(None) => return Ok(latest),
+                Err(e) if latest.is_none() => return Err(e),
+                Err(_) => return Ok(latest),
+            }
+        }
+    }
+}
+
+impl SampleStream {
+    /// Transform every sample of the stream with `f`, on a separate thread.
+    ///
+    /// The thread exits when either stream ends, which for the returned one is only noticed
+    /// on the next sample.
+    pub fn map_samples<F>(self, mut f: F) -> SampleStream
+    where
+        F: FnMut(OrientationSample) -> OrientationSample + Send + 'static,
+    {
+        let (sender, _, stream) = SampleStream::channel();
+
+        thread::spawn(move || {
+            for sample in self {
+                if sender.send(f(sample)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        stream
+    }
+}
+
+impl<T> Iterator for Stream<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+/// The stream has ended, since its producer is gone.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("sample stream has ended")]
+pub struct StreamEnded;
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Self {
+            senders: Mutex::default(),
+        }
+    }
+}
+
+impl<T: Clone> Subscribers<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new stream, which receives all the updates published from now on.
+    pub fn subscribe(&self) -> Stream<T> {
+        let (sender, _, stream) = Stream::channel();
+        self.senders.lock().unwrap().push(sender);
+
+        stream
+    }
+
+    /// Deliver the update to all the streams, forgetting the ones that have been dropped.
+    pub fn publish(&self, update: &T) {
+        self.senders
+            .lock()
+            .unwrap()
+            .retain(|sender| sender.send(update.clone()).is_ok());
+    }
+
+    /// Number of the streams alive as of the last [publish] call.
+    ///
+    /// [publish]: Subscribers::publish
+    pub fn len(&self) -> usize {
+        self.senders.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Current status of a head tracker, which is published to the subscribed streams whenever
+/// it changes.
+///
+/// This is meant to be used by the implementations reporting their status.
+pub struct StatusReporter {
+    status: Mutex<Status>,
+    subscribers: Subscribers<Status>,
+}
+
+impl StatusReporter {
+    pub fn new(status: Status) -> Self {
+        Self {
+            status: Mutex::new(status),
+            subscribers: Subscribers::new(),
+        }
+    }
+
+    pub fn get(&self) -> Status {
+        *self.status.lock().unwrap()
+    }
+
+    /// Change the status, publishing it if it differs from the current one.
+    ///
+    /// Returns whether the status has changed.
+    pub fn set(&self, status: Status) -> bool {
+        let mut current = self.status.lock().unwrap();
+
+        if *current == status {
+            return false;
+        }
+
+        *current = status;
+        // Published under the lock, so that the streams see the changes in order
+        self.subscribers.publish(&status);
+
+        true
+    }
+
+    /// Create a new stream, which receives all the status changes from now on.
+    pub fn subscribe(&self) -> StatusStream {
+        self.subscribers.subscribe()
+    }
+}
+
+/// Starts as [Status::Stopped], as the motion updates are not running initially.
+impl Default for StatusReporter {
+    fn default() -> Self {
+        Self::new(Status::Stopped)
+    }
+}
+
+/// Samples and status of a head tracker whose samples are produced in the background,
+/// e.g. received from a device on a separate thread.
+///
+/// The samples are only accepted between [start] and [stop], so that a late sample doesn't
+/// bring a stopped tracker back to life.
+///
+/// This is meant to be used by the implementations providing native streams, which may forward
+/// [HeadTracker::pull_sample], [HeadTracker::subscribe], [HeadTracker::status] and
+/// [HeadTracker::subscribe_status] to it.
+///
+/// [start]: Publisher::start
+/// [stop]: Publisher::stop
+pub struct Publisher {
+    state: Mutex<PublisherState>,
+    subscribers: Subscribers,
+    status: StatusReporter,
+}
+
+#[derive(Default)]
+struct PublisherState {
+    running: bool,
+    latest: Option<OrientationSample>,
+}
+
+impl Publisher {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::default(),
+            subscribers: Subscribers::new(),
+            status: StatusReporter::default(),
+        }
+    }
+
+    /// Start accepting the samples, forgetting the ones of the previous session, which are
+    /// stale; the status is [Waiting](Status::Waiting) until the first sample arrives.
+    pub fn start(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        *state = PublisherState {
+            running: true,
+            latest: None,
+        };
+
+        self.status.set(Status::Waiting);
+    }
+
+    /// Stop accepting the samples; the status is [Stopped](Status::Stopped) from now on.
+    pub fn stop(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.running = false;
+        self.status.set(Status::Stopped);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.lock().unwrap().running
+    }
+
+    /// Make the sample the latest one and deliver it to all the streams; the status becomes
+    /// [Active](Status::Active).
+    ///
+    /// Returns `false`, dropping the sample, if the publisher is stopped.
+    pub fn publish(&self, sample: OrientationSample) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.running {
+            return false;
+        }
+
+        // Published under the lock, so that the samples and the status changes are in order
+        self.subscribers.publish(&sample);
+        self.status.set(Status::Active);
+        state.latest = Some(sample);
+
+        true
+    }
+
+    /// Report that the device has stopped sending samples; the status is
+    /// [Disconnected](Status::Disconnected) until the next sample.
+    ///
+    /// Does nothing if the publisher is stopped.
+    pub fn disconnect(&self) {
+        let state = self.state.lock().unwrap();
+
+        if state.running {
+            self.status.set(Status::Disconnected);
+        }
+    }
+
+    pub fn latest(&self) -> Option<OrientationSample> {
+        self.state.lock().unwrap().latest.clone()
+    }
+
+    /// Create a new stream, which receives all the samples published from now on.
+    pub fn subscribe(&self) -> SampleStream {
+        self.subscribers.subscribe()
+    }
+
+    pub fn status(&self) -> Status {
+        self.status.get()
+    }
+
+    /// Create a new stream, which receives all the status changes from now on.
+    pub fn subscribe_status(&self) -> StatusStream {
+        self.status.subscribe()
+    }
+}
+
+impl Default for Publisher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turn a pull-only head tracker into a stream, by pulling the samples every `interval`
+/// on a separate thread.
+///
+/// Only new samples (as told by their sequence numbers) are delivered. The thread exits once
+/// the stream is dropped. Starting and stopping the motion updates is up to the caller.
+pub fn poll<T>(tracker: Arc<T>, interval: Duration) -> SampleStream
+where
+    T: HeadTracker + Send + Sync + ?Sized + 'static,
+{
+    let (sender, alive, stream) = SampleStream::channel();
+
+    thread::spawn(move || {
+        let mut last_sequence = None;
+
+        while alive.strong_count() > 0 {
+            if let Some(sample) = tracker.pull_sample() {
+                if last_sequence != Some(sample.sequence) {
+                    last_sequence = Some(sample.sequence);
+
+                    if sender.send(sample).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            thread::sleep(interval);
+        }
+    });
+
+    stream
+}
+
+/// Subscribe to the updates of the `tracker`, falling back to [poll] with the given interval
+/// if it doesn't provide a native stream.
+pub fn samples<T>(tracker: Arc<T>, poll_interval: Duration) -> SampleStream
+where
+    T: HeadTracker + Send + Sync + ?Sized + 'static,
+{
+    match tracker.subscribe() {
+        Some(stream) => stream,
+        None => poll(tracker, poll_interval),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Instant;
+
+    use super::*;
+    use crate::{Error, UnitQuaternion, UnknownError};
+
+    /// Pull-only tracker, which produces a new sample every other pull.
+    #[derive(Default)]
+    struct PullOnly {
+        pulls: AtomicU64,
+    }
+
+    impl HeadTracker for PullOnly {
+        fn start_motion_updates(&self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn pull_sample(&self) -> Option<OrientationSample> {
+            let pull = self.pulls.fetch_add(1, Ordering::SeqCst);
+
+            Some(OrientationSample::new(
+                UnitQuaternion::identity(),
+                Instant::now(),
+                pull / 2,
+            ))
+        }
+
+        fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_poll_delivers_new_samples_only() {
+        let stream = samples(Arc::new(PullOnly::default()), Duration::from_millis(1));
+
+        let sequences: Vec<_> = stream.take(3).map(|sample| sample.sequence).collect();
+
+        assert_eq!(sequences, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_poll_stops_when_stream_is_dropped() {
+        let tracker = Arc::new(PullOnly::default());
+        let stream = poll(tracker.clone(), Duration::from_millis(1));
+
+        assert!(stream.recv().is_some());
+        drop(stream);
+
+        // Give the thread a chance to notice
+        thread::sleep(Duration::from_millis(20));
+        let pulls = tracker.pulls.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(tracker.pulls.load(Ordering::SeqCst), pulls);
+        assert_eq!(Arc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn test_subscribers() {
+        let subscribers = Subscribers::new();
+        let first = subscribers.subscribe();
+        let second = subscribers.subscribe();
+        let sample = OrientationSample::new(UnitQuaternion::identity(), Instant::now(), 7);
+
+        drop(second);
+        subscribers.publish(&sample);
+
+        assert_eq!(subscribers.len(), 1);
+        assert_eq!(first.try_recv(), Ok(Some(sample)));
+        assert_eq!(first.try_recv(), Ok(None));
+
+        drop(subscribers);
+        assert_eq!(first.latest(), Err(StreamEnded));
+    }
+
+    #[test]
+    fn test_status_reporter() {
+        let reporter = StatusReporter::default();
+        let stream = reporter.subscribe();
+
+        assert!(reporter.set(Status::Waiting));
+        assert!(!reporter.set(Status::Waiting));
+        assert!(reporter.set(Status::Active));
+
+        assert_eq!(reporter.get(), Status::Active);
+        assert_eq!(stream.try_recv(), Ok(Some(Status::Waiting)));
+        assert_eq!(stream.try_recv(), Ok(Some(Status::Active)));
+        assert_eq!(stream.try_recv(), Ok(None));
+    }
+
+    #[test]
+    fn test_publisher() {
+        let publisher = Publisher::new();
+        let samples = publisher.subscribe();
+        let statuses = publisher.subscribe_status();
+        let sample =
+            |sequence| OrientationSample::new(UnitQuaternion::identity(), Instant::now(), sequence);
+
+        // Dropped while stopped
+        assert!(!publisher.publish(sample(0)));
+        assert_eq!(publisher.status(), Status::Stopped);
+
+        publisher.start();
+        assert_eq!(publisher.status(), Status::Waiting);
+
+        assert!(publisher.publish(sample(1)));
+        publisher.disconnect();
+        assert_eq!(publisher.latest().unwrap().sequence, 1);
+
+        publisher.stop();
+        publisher.disconnect();
+        assert!(!publisher.publish(sample(2)));
+
+        // The samples of the previous session are forgotten
+        publisher.start();
+        assert_eq!(publisher.latest(), None);
+
+        assert_eq!(samples.try_recv().unwrap().unwrap().sequence, 1);
+        assert_eq!(samples.try_recv(), Ok(None));
+
+        for status in [
+            Status::Waiting,
+            Status::Active,
+            Status::Disconnected,
+            Status::Stopped,
+            Status::Waiting,
+        ] {
+            assert_eq!(statuses.try_recv(), Ok(Some(status)));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! # Testing
+//!
+//! Trackers for testing the wrappers and backends built on top of [HeadTracker], available
+//! with the `testing` feature.
+
+use std::sync::Mutex;
+
+use crate::stream::{SampleStream, Subscribers};
+use crate::{Error, HeadTracker, OrientationSample, UnknownError};
+
+/// Tracker reporting whatever sample it has been given.
+#[derive(Default)]
+pub struct Manual {
+    sample: Mutex<Option<OrientationSample>>,
+    subscribers: Subscribers,
+}
+
+impl Manual {
+    /// Report `sample` from now on, delivering it to the subscribed streams as well.
+    pub fn set(&self, sample: OrientationSample) {
+        *self.sample.lock().unwrap() = Some(sample.clone());
+        self.subscribers.publish(&sample);
+    }
+}
+
+impl HeadTracker for Manual {
+    fn start_motion_updates(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn pull_sample(&self) -> Option<OrientationSample> {
+        self.sample.lock().unwrap().clone()
+    }
+
+    fn subscribe(&self) -> Option<SampleStream> {
+        Some(self.subscribers.subscribe())
+    }
+
+    fn stop_motion_updates(&self) -> Result<(), UnknownError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::na::Vector3;
+use crate::{Orientation, Point3};
+
+/// Orientation given as yaw, pitch and roll angles, in radians, in the frame of the project
+/// (see [Frame::IRT](crate::Frame::IRT)).
+///
+/// The rotations are applied to the head looking forward in the following order:
+/// 1. yaw: rotation about the z-axis; positive values turn the head to the left;
+/// 2. pitch: rotation about the (rotated) x-axis; positive values tilt the head up;
+/// 3. roll: rotation about the (rotated) y-axis; positive values tilt the head to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct YawPitchRoll {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+}
+
+impl YawPitchRoll {
+    pub fn new(yaw: f32, pitch: f32, roll: f32) -> Self {
+        Self { yaw, pitch, roll }
+    }
+
+    /// Extract the angles from the orientation.
+    ///
+    /// The yaw and roll are in range `[-π, π]`, the pitch is in range `[-π/2, π/2]`.
+    /// When looking straight up or down, the yaw and roll are indistinguishable, so the roll
+    /// is reported as zero.
+    pub fn from_orientation(orientation: &Orientation) -> Self {
+        let m = orientation.to_rotation_matrix().into_inner();
+
+        let pitch = m[(2, 1)].clamp(-1.0, 1.0).asin();
+
+        // cos(pitch) is close to zero, i.e. the head is looking straight up or down
+        if 1.0 - m[(2, 1)].abs() < 1e-6 {
+            return Self::new(m[(1, 0)].atan2(m[(0, 0)]), pitch, 0.0);
+        }
+
+        Self {
+            yaw: (-m[(0, 1)]).atan2(m[(1, 1)]),
+            pitch,
+            roll: (-m[(2, 0)]).atan2(m[(2, 2)]),
+        }
+    }
+
+    pub fn to_orientation(&self) -> Orientation {
+        let yaw = Orientation::from_axis_angle(&Vector3::z_axis(), self.yaw);
+        let pitch = Orientation::from_axis_angle(&Vector3::x_axis(), self.pitch);
+        let roll = Orientation::from_axis_angle(&Vector3::y_axis(), self.roll);
+
+        yaw * pitch * roll
+    }
+
+    /// Direction the head is looking in, as a unit vector.
+    pub fn forward(&self) -> Vector3<f32> {
+        (self.to_orientation() * Point3::new(0.0, 1.0, 0.0)).coords
+    }
+}
+
+impl From<Orientation> for YawPitchRoll {
+    fn from(value: Orientation) -> Self {
+        Self::from_orientation(&value)
+    }
+}
+
+impl From<YawPitchRoll> for Orientation {
+    fn from(value: YawPitchRoll) -> Self {
+        value.to_orientation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn assert_angles_eq(actual: YawPitchRoll, expected: YawPitchRoll) {
+        assert_relative_eq!(actual.yaw, expected.yaw, epsilon = 1e-5);
+        assert_relative_eq!(actual.pitch, expected.pitch, epsilon = 1e-5);
+        assert_relative_eq!(actual.roll, expected.roll, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_directions() {
+        let left = YawPitchRoll::new(FRAC_PI_2, 0.0, 0.0).forward();
+        let up = YawPitchRoll::new(0.0, FRAC_PI_2, 0.0).forward();
+
+        assert_relative_eq!(left, -Vector3::x(), epsilon = 1e-6);
+        assert_relative_eq!(up, Vector3::z(), epsilon = 1e-6);
+
+        // Rolling to the right lowers the right ear, i.e. the up vector leans to the right
+        let rolled = YawPitchRoll::new(0.0, 0.0, FRAC_PI_4).to_orientation() * Vector3::z();
+        assert!(rolled.x > 0.0);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cases = [
+            YawPitchRoll::new(0.3, -0.2, 0.1),
+            YawPitchRoll::new(-2.5, 1.2, -3.0),
+            YawPitchRoll::new(PI - 0.1, -1.5, 0.7),
+            YawPitchRoll::default(),
+        ];
+
+        for angles in cases {
+            assert_angles_eq(angles.to_orientation().into(), angles);
+        }
+    }
+
+    #[test]
+    fn test_looking_straight_up() {
+        let orientation = YawPitchRoll::new(0.5, FRAC_PI_2, 0.3).to_orientation();
+        let angles = YawPitchRoll::from_orientation(&orientation);
+
+        assert_relative_eq!(angles.pitch, FRAC_PI_2, epsilon = 1e-3);
+        assert_eq!(angles.roll, 0.0);
+        assert_relative_eq!(angles.to_orientation(), orientation, epsilon = 1e-3);
+    }
+}
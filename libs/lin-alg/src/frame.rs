@@ -0,0 +1,214 @@
+use crate::na::{Matrix3, Vector3};
+use crate::{Orientation, Point3, Quaternion};
+
+/// Direction, as seen by the user in the frame used by the project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Right,
+    Left,
+    Forward,
+    Backward,
+    Up,
+    Down,
+}
+
+/// Cartesian coordinate frame, defined by the directions its axes point to.
+///
+/// The frame used throughout the project is [Frame::IRT]; other frames are only needed when
+/// exchanging the data with external APIs, e.g. the head-tracking ones, which are free to choose
+/// any convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    x: Axis,
+    y: Axis,
+    z: Axis,
+}
+
+impl Axis {
+    /// Unit vector of the direction in [Frame::IRT].
+    pub fn vector(&self) -> Vector3<f32> {
+        match self {
+            Axis::Right => Vector3::x(),
+            Axis::Left => -Vector3::x(),
+            Axis::Forward => Vector3::y(),
+            Axis::Backward => -Vector3::y(),
+            Axis::Up => Vector3::z(),
+            Axis::Down => -Vector3::z(),
+        }
+    }
+
+    const fn dimension(&self) -> u8 {
+        match self {
+            Axis::Right | Axis::Left => 0,
+            Axis::Forward | Axis::Backward => 1,
+            Axis::Up | Axis::Down => 2,
+        }
+    }
+}
+
+impl Frame {
+    /// The frame of the project: x-axis points to the right, y-axis points forward, z-axis
+    /// points up, as illustrated in `libs/ht/docs/coordinate-system.png`.
+    pub const IRT: Frame = Frame::new(Axis::Right, Axis::Forward, Axis::Up);
+
+    /// Right-handed, Y-up frame, as used by OpenGL: x-axis points to the right, y-axis points up,
+    /// z-axis points backward.
+    pub const RH_Y_UP: Frame = Frame::new(Axis::Right, Axis::Up, Axis::Backward);
+
+    /// Right-handed, Z-up frame, as used by ROS: x-axis points forward, y-axis points to the left,
+    /// z-axis points up.
+    pub const RH_Z_UP: Frame = Frame::new(Axis::Forward, Axis::Left, Axis::Up);
+
+    /// Left-handed, Y-up frame, as used by Direct3D and Unity: x-axis points to the right,
+    /// y-axis points up, z-axis points forward.
+    pub const LH_Y_UP: Frame = Frame::new(Axis::Right, Axis::Up, Axis::Forward);
+
+    /// Create a frame out of the axis directions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any two of the axes are parallel.
+    pub const fn new(x: Axis, y: Axis, z: Axis) -> Self {
+        let (dx, dy, dz) = (x.dimension(), y.dimension(), z.dimension());
+
+        assert!(
+            dx != dy && dy != dz && dx != dz,
+            "axes of a frame must be perpendicular to each other"
+        );
+
+        Self { x, y, z }
+    }
+
+    pub fn x(&self) -> Axis {
+        self.x
+    }
+
+    pub fn y(&self) -> Axis {
+        self.y
+    }
+
+    pub fn z(&self) -> Axis {
+        self.z
+    }
+
+    /// Matrix converting the coordinates in this frame to the coordinates in the `target` frame.
+    pub fn matrix_to(&self, target: &Frame) -> Matrix3<f32> {
+        target.basis().transpose() * self.basis()
+    }
+
+    /// Convert the `point` given in this frame to the `target` frame.
+    pub fn convert_point(&self, target: &Frame, point: &Point3) -> Point3 {
+        (self.matrix_to(target) * point.coords).into()
+    }
+
+    /// Convert the `vector` (e.g. velocity) given in this frame to the `target` frame.
+    pub fn convert_vector(&self, target: &Frame, vector: &Vector3<f32>) -> Vector3<f32> {
+        self.matrix_to(target) * vector
+    }
+
+    /// Convert the `orientation` given in this frame to the `target` frame, so that it describes
+    /// the same physical rotation.
+    pub fn convert_orientation(&self, target: &Frame, orientation: &Orientation) -> Orientation {
+        let matrix = self.matrix_to(target);
+
+        // Mirroring the frame flips the direction of rotation around the axis, which the sign
+        // of the determinant (either 1 or -1 for these matrices) accounts for
+        let axis = matrix.determinant() * matrix * orientation.imag();
+
+        Orientation::new_unchecked(Quaternion::from_parts(orientation.w, axis))
+    }
+
+    /// Columns are the axes of the frame, given in [Frame::IRT].
+    fn basis(&self) -> Matrix3<f32> {
+        Matrix3::from_columns(&[self.x.vector(), self.y.vector(), self.z.vector()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    const FRAMES: [Frame; 4] = [Frame::IRT, Frame::RH_Y_UP, Frame::RH_Z_UP, Frame::LH_Y_UP];
+
+    #[test]
+    fn test_convert_point() {
+        let forward = Point3::new(0.0, 2.0, 0.0);
+
+        assert_eq!(
+            Frame::IRT.convert_point(&Frame::RH_Y_UP, &forward),
+            Point3::new(0.0, 0.0, -2.0)
+        );
+        assert_eq!(
+            Frame::IRT.convert_point(&Frame::RH_Z_UP, &forward),
+            Point3::new(2.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            Frame::IRT.convert_point(&Frame::LH_Y_UP, &forward),
+            Point3::new(0.0, 0.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_point_round_trip() {
+        let point = Point3::new(1.0, -2.0, 3.0);
+
+        for from in FRAMES {
+            for to in FRAMES {
+                let converted = from.convert_point(&to, &point);
+                assert_eq!(to.convert_point(&from, &converted), point);
+            }
+        }
+    }
+
+    #[test]
+    fn test_orientation_describes_same_rotation() {
+        let orientation = Orientation::from_euler_angles(0.3, -0.7, 1.2);
+        let point = Point3::new(1.0, 2.0, -0.5);
+
+        for from in FRAMES {
+            for to in FRAMES {
+                let converted = from.convert_orientation(&to, &orientation);
+
+                // Rotating, then converting is the same as converting, then rotating
+                assert_relative_eq!(
+                    from.convert_point(&to, &(orientation * point)),
+                    converted * from.convert_point(&to, &point),
+                    epsilon = 1e-5
+                );
+                assert_relative_eq!(
+                    to.convert_orientation(&from, &converted),
+                    orientation,
+                    epsilon = 1e-6
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mirrored_orientation() {
+        // Turning to the left in the project frame is turning to the left in Y-up frames, too,
+        // even though the rotation angle about the up axis is negated in the left-handed one
+        let turn_left = Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2);
+
+        assert_relative_eq!(
+            Frame::IRT.convert_orientation(&Frame::RH_Y_UP, &turn_left),
+            Orientation::from_axis_angle(&Vector3::y_axis(), FRAC_PI_2),
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            Frame::IRT.convert_orientation(&Frame::LH_Y_UP, &turn_left),
+            Orientation::from_axis_angle(&Vector3::y_axis(), -FRAC_PI_2),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_parallel_axes() {
+        Frame::new(Axis::Right, Axis::Left, Axis::Up);
+    }
+}
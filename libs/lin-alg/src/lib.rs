@@ -2,8 +2,17 @@
 //!
 //! This module contains various useful definitions that are used for operations on 3D space.
 //! Most definitions are simply aliases to their implementation in `nalgebra` crate.
+//!
+//! With `serde` feature enabled, all the definitions can be serialized and deserialized.
 
+pub use euler::YawPitchRoll;
+pub use frame::{Axis, Frame};
 pub use nalgebra as na;
+pub use spherical::Spherical;
+
+mod euler;
+mod frame;
+mod spherical;
 
 /// Generic, non-normalized quaternion.
 pub type Quaternion = na::Quaternion<f32>;
@@ -16,3 +25,6 @@ pub type Orientation = UnitQuaternion;
 
 /// Point in 3D space.
 pub type Point3 = na::Point3<f32>;
+
+/// Translation, rotation and uniform scaling in 3D space, applied in the reverse order.
+pub type Transform = na::Similarity3<f32>;
@@ -0,0 +1,143 @@
+use crate::Point3;
+
+/// Position in spherical coordinates, relative to the origin of the coordinate system
+/// used by the project: x-axis points to the right, y-axis points forward (away from the user)
+/// and z-axis points up.
+///
+/// The illustration of the coordinate system can be found in the source tree of head-tracking
+/// interface module, at `libs/ht/docs/coordinate-system.png`.
+///
+/// All the angles are in radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spherical {
+    /// Angle in the horizontal (xy) plane, measured from the y-axis: zero is straight ahead,
+    /// positive values are to the right, negative values are to the left. In range `[-π, π]`.
+    pub azimuth: f32,
+    /// Angle above the horizontal plane; negative values are below it. In range `[-π/2, π/2]`.
+    pub elevation: f32,
+    /// Distance to the origin, never negative.
+    pub distance: f32,
+}
+
+impl Spherical {
+    pub fn new(azimuth: f32, elevation: f32, distance: f32) -> Self {
+        Self {
+            azimuth,
+            elevation,
+            distance,
+        }
+    }
+
+    /// Convert the point to spherical coordinates.
+    ///
+    /// Both angles of the origin are zero; the azimuth of the points lying on the z-axis is zero.
+    pub fn from_point(point: &Point3) -> Self {
+        let horizontal = point.x.hypot(point.y);
+
+        Self {
+            azimuth: point.x.atan2(point.y),
+            elevation: point.z.atan2(horizontal),
+            distance: point.coords.norm(),
+        }
+    }
+
+    /// Convert back to Cartesian coordinates.
+    pub fn to_point(&self) -> Point3 {
+        let (sin_azimuth, cos_azimuth) = self.azimuth.sin_cos();
+        let (sin_elevation, cos_elevation) = self.elevation.sin_cos();
+        let horizontal = self.distance * cos_elevation;
+
+        Point3::new(
+            horizontal * sin_azimuth,
+            horizontal * cos_azimuth,
+            self.distance * sin_elevation,
+        )
+    }
+}
+
+impl From<Point3> for Spherical {
+    fn from(value: Point3) -> Self {
+        Self::from_point(&value)
+    }
+}
+
+impl From<Spherical> for Point3 {
+    fn from(value: Spherical) -> Self {
+        value.to_point()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn assert_spherical_eq(actual: Spherical, expected: Spherical) {
+        assert_relative_eq!(actual.azimuth, expected.azimuth, epsilon = 1e-6);
+        assert_relative_eq!(actual.elevation, expected.elevation, epsilon = 1e-6);
+        assert_relative_eq!(actual.distance, expected.distance, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_axes() {
+        // See libs/ht/docs/coordinate-system.png
+        let cases = [
+            (Point3::new(0.0, 2.0, 0.0), Spherical::new(0.0, 0.0, 2.0)),
+            (
+                Point3::new(2.0, 0.0, 0.0),
+                Spherical::new(FRAC_PI_2, 0.0, 2.0),
+            ),
+            (
+                Point3::new(-2.0, 0.0, 0.0),
+                Spherical::new(-FRAC_PI_2, 0.0, 2.0),
+            ),
+            (Point3::new(0.0, -2.0, 0.0), Spherical::new(PI, 0.0, 2.0)),
+            (
+                Point3::new(0.0, 0.0, 2.0),
+                Spherical::new(0.0, FRAC_PI_2, 2.0),
+            ),
+            (
+                Point3::new(0.0, 0.0, -2.0),
+                Spherical::new(0.0, -FRAC_PI_2, 2.0),
+            ),
+        ];
+
+        for (point, spherical) in cases {
+            assert_spherical_eq(point.into(), spherical);
+        }
+    }
+
+    #[test]
+    fn test_front_right_above() {
+        let spherical = Spherical::from(Point3::new(1.0, 1.0, 2.0_f32.sqrt()));
+
+        assert_spherical_eq(spherical, Spherical::new(FRAC_PI_4, FRAC_PI_4, 2.0));
+    }
+
+    #[test]
+    fn test_origin() {
+        assert_eq!(
+            Spherical::from(Point3::origin()),
+            Spherical::new(0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let points = [
+            Point3::new(1.0, 2.0, 3.0),
+            Point3::new(-4.0, 0.5, -1.0),
+            Point3::new(-0.25, -3.0, 0.0),
+            Point3::new(0.0, 0.0, 5.0),
+        ];
+
+        for point in points {
+            let spherical = Spherical::from(point);
+            assert_relative_eq!(Point3::from(spherical), point, epsilon = 1e-5);
+        }
+    }
+}
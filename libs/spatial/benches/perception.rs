@@ -0,0 +1,65 @@
+use std::f32::consts::*;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nalgebra::{point, Vector3};
+
+use irt_spatial::{Cone, DistanceModel, Listener, Orientation, Scene, Source};
+
+const SCENE_SIZES: [usize; 3] = [16, 256, 1024];
+
+fn scene(size: usize) -> Scene {
+    (0..size)
+        .map(|i| {
+            let angle = i as f32 * TAU / size as f32;
+
+            Source::with_location(point![angle.cos(), angle.sin(), 0.5] * (1.0 + i as f32 % 7.0))
+                .name(format!("source {i}"))
+                .attenuation(DistanceModel::InverseClamped)
+                .orientation(Orientation::from_axis_angle(&Vector3::z_axis(), -angle))
+                .cone(Cone::new(FRAC_PI_2, PI, 0.25))
+                .velocity(Vector3::new(0.0, 0.0, 0.1))
+                .build()
+        })
+        .collect()
+}
+
+fn listener() -> Listener {
+    Listener::new_with_location(
+        point![0.3, -0.2, 0.0],
+        Orientation::from_euler_angles(0.1, 0.2, FRAC_PI_3),
+    )
+}
+
+fn perception(c: &mut Criterion) {
+    let mut group = c.benchmark_group("perception");
+    let listener = listener();
+
+    for size in SCENE_SIZES {
+        let scene = scene(size);
+
+        group.bench_with_input(BenchmarkId::new("cloning", size), &scene, |b, scene| {
+            b.iter(|| listener.baseline_perceived_scene(black_box(scene)))
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("perceived_scene", size),
+            &scene,
+            |b, scene| b.iter(|| listener.perceived_scene(black_box(scene))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("perceive_into", size),
+            &scene,
+            |b, scene| {
+                let mut out = Scene::new(Vec::new());
+                b.iter(|| listener.perceive_into(black_box(scene), &mut out))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, perception);
+criterion_main!(benches);
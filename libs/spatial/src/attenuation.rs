@@ -0,0 +1,264 @@
+//! # Distance attenuation
+//!
+//! Models describing how the gain of a source decreases as the listener moves away from it.
+//! The formulas follow the ones used by OpenAL and Web Audio API, so the scenes authored for
+//! those are expected to sound similarly here.
+
+/// Distance attenuation model.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DistanceModel {
+    /// No attenuation: the gain stays the same regardless of distance.
+    #[default]
+    None,
+    /// `ref / (ref + rolloff * (d - ref))`, where the distance is never less than `ref`.
+    Inverse,
+    /// Same as [DistanceModel::Inverse], but the distance is also capped at `max`.
+    InverseClamped,
+    /// `1 - rolloff * (d - ref) / (max - ref)`, where the distance is clamped to `[ref, max]`.
+    Linear,
+    /// `(d / ref) ^ -rolloff`, where the distance is never less than `ref`.
+    Exponential,
+    /// Arbitrary curve, mapping the distance to gain directly.
+    ///
+    /// Reference distance, maximum distance and rolloff are not applied to custom curves.
+    Custom(Curve),
+}
+
+/// Piecewise-linear curve defined by `(distance, gain)` points.
+///
+/// The gain is interpolated linearly between the adjacent points and held constant
+/// before the first and after the last point.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Vec<(f32, f32)>", into = "Vec<(f32, f32)>")
+)]
+pub struct Curve {
+    points: Vec<(f32, f32)>,
+}
+
+/// Distance attenuation settings of a source.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Attenuation {
+    pub model: DistanceModel,
+    /// Distance at which the attenuation starts; the source is heard at full gain within it.
+    pub reference_distance: f32,
+    /// Distance beyond which the gain doesn't decrease any further, for the models that
+    /// clamp the distance.
+    pub max_distance: f32,
+    /// How quickly the gain decreases with distance.
+    pub rolloff: f32,
+}
+
+impl Curve {
+    /// Create a curve out of `(distance, gain)` points, which may be given in any order.
+    pub fn new(mut points: Vec<(f32, f32)>) -> Self {
+        points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    /// Evaluate the curve at `distance`; an empty curve doesn't attenuate at all.
+    pub fn gain_at(&self, distance: f32) -> f32 {
+        let (Some(&first), Some(&last)) = (self.points.first(), self.points.last()) else {
+            return 1.0;
+        };
+
+        if distance <= first.0 {
+            return first.1;
+        }
+
+        if distance >= last.0 {
+            return last.1;
+        }
+
+        let upper = self.points.partition_point(|&(d, _)| d <= distance);
+        let (d0, g0) = self.points[upper - 1];
+        let (d1, g1) = self.points[upper];
+
+        g0 + (g1 - g0) * (distance - d0) / (d1 - d0)
+    }
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        // Same defaults as in Web Audio API
+        Self {
+            model: DistanceModel::None,
+            reference_distance: 1.0,
+            max_distance: 10000.0,
+            rolloff: 1.0,
+        }
+    }
+}
+
+impl Attenuation {
+    pub fn new(model: DistanceModel) -> Self {
+        Self {
+            model,
+            ..Default::default()
+        }
+    }
+
+    /// Compute the gain, in range `[0, 1]` for the built-in models, at the given distance
+    /// between the source and the listener.
+    pub fn gain_at(&self, distance: f32) -> f32 {
+        let reference = self.reference_distance;
+        let max = self.max_distance.max(reference);
+
+        let gain = match &self.model {
+            DistanceModel::None => 1.0,
+            DistanceModel::Inverse => inverse(distance.max(reference), reference, self.rolloff),
+            DistanceModel::InverseClamped => {
+                inverse(distance.clamp(reference, max), reference, self.rolloff)
+            }
+            DistanceModel::Linear => {
+                if max == reference {
+                    return 1.0;
+                }
+
+                let distance = distance.clamp(reference, max);
+                1.0 - self.rolloff * (distance - reference) / (max - reference)
+            }
+            DistanceModel::Exponential => {
+                if reference <= 0.0 {
+                    return 1.0;
+                }
+
+                (distance.max(reference) / reference).powf(-self.rolloff)
+            }
+            DistanceModel::Custom(curve) => curve.gain_at(distance),
+        };
+
+        if gain.is_nan() {
+            1.0
+        } else {
+            gain.max(0.0)
+        }
+    }
+}
+
+impl From<Vec<(f32, f32)>> for Curve {
+    fn from(value: Vec<(f32, f32)>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Curve> for Vec<(f32, f32)> {
+    fn from(value: Curve) -> Self {
+        value.points
+    }
+}
+
+impl From<DistanceModel> for Attenuation {
+    fn from(value: DistanceModel) -> Self {
+        Self::new(value)
+    }
+}
+
+fn inverse(distance: f32, reference: f32, rolloff: f32) -> f32 {
+    let denominator = reference + rolloff * (distance - reference);
+
+    if denominator <= 0.0 {
+        return 1.0;
+    }
+
+    reference / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn attenuation(model: DistanceModel) -> Attenuation {
+        Attenuation {
+            model,
+            reference_distance: 1.0,
+            max_distance: 5.0,
+            rolloff: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_no_attenuation() {
+        let attenuation = attenuation(DistanceModel::None);
+
+        assert_eq!(attenuation.gain_at(0.0), 1.0);
+        assert_eq!(attenuation.gain_at(100.0), 1.0);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let attenuation = attenuation(DistanceModel::Inverse);
+
+        assert_eq!(attenuation.gain_at(0.5), 1.0);
+        assert_relative_eq!(attenuation.gain_at(2.0), 0.5);
+        assert_relative_eq!(attenuation.gain_at(10.0), 0.1);
+    }
+
+    #[test]
+    fn test_inverse_clamped() {
+        let attenuation = attenuation(DistanceModel::InverseClamped);
+
+        assert_relative_eq!(attenuation.gain_at(2.0), 0.5);
+        assert_relative_eq!(attenuation.gain_at(10.0), 0.2);
+    }
+
+    #[test]
+    fn test_linear() {
+        let attenuation = attenuation(DistanceModel::Linear);
+
+        assert_eq!(attenuation.gain_at(1.0), 1.0);
+        assert_relative_eq!(attenuation.gain_at(3.0), 0.5);
+        assert_eq!(attenuation.gain_at(5.0), 0.0);
+        assert_eq!(attenuation.gain_at(10.0), 0.0);
+    }
+
+    #[test]
+    fn test_exponential() {
+        let attenuation = Attenuation {
+            rolloff: 2.0,
+            ..attenuation(DistanceModel::Exponential)
+        };
+
+        assert_eq!(attenuation.gain_at(0.1), 1.0);
+        assert_relative_eq!(attenuation.gain_at(2.0), 0.25);
+    }
+
+    #[test]
+    fn test_custom_curve() {
+        let curve = Curve::new(vec![(4.0, 0.0), (0.0, 1.0), (2.0, 0.8)]);
+        let attenuation = attenuation(DistanceModel::Custom(curve));
+
+        assert_eq!(attenuation.gain_at(-1.0), 1.0);
+        assert_relative_eq!(attenuation.gain_at(1.0), 0.9);
+        assert_relative_eq!(attenuation.gain_at(3.0), 0.4);
+        assert_eq!(attenuation.gain_at(8.0), 0.0);
+    }
+
+    #[test]
+    fn test_degenerate_parameters() {
+        let zero_reference = Attenuation {
+            reference_distance: 0.0,
+            ..attenuation(DistanceModel::Inverse)
+        };
+        let no_range = Attenuation {
+            max_distance: 1.0,
+            ..attenuation(DistanceModel::Linear)
+        };
+
+        assert_eq!(zero_reference.gain_at(0.0), 1.0);
+        assert_eq!(no_range.gain_at(3.0), 1.0);
+        assert_eq!(Curve::default().gain_at(3.0), 1.0);
+    }
+}
@@ -0,0 +1,31 @@
+//! [Listener::perceived_scene] as it was before [Listener::perceive_into] was introduced,
+//! copied verbatim and kept only as the baseline of the perception benchmarks.
+
+use crate::{Doppler, Listener, Perception, Scene, Source};
+
+impl Listener {
+    #[doc(hidden)]
+    pub fn baseline_perceived_scene(&self, scene: &Scene) -> Scene {
+        // TODO(max-khm): This could be potentially optimized with SIMD calculations
+        scene.map_sources(|id, source| {
+            self.perceive(&scene.world_source(id, source), &scene.doppler)
+        })
+    }
+
+    fn perceive(&self, source: &Source, doppler: &Doppler) -> Source {
+        let offset = source.position - self.location.coords;
+        let distance = offset.coords.norm();
+
+        let mut perceived = source
+            .relocated(offset)
+            .perceived_from(&self.orientation.inverse());
+
+        perceived.perception = Perception {
+            distance_attenuation: source.attenuation.gain_at(distance),
+            directivity_gain: source.cone.gain_towards(&source.forward(), &-offset.coords),
+            doppler_factor: doppler.factor(source.motion(), self.motion()),
+        };
+
+        perceived
+    }
+}
@@ -0,0 +1,112 @@
+//! # Source directivity
+//!
+//! Directional sources radiate at full gain within the inner cone, at the outer gain outside of
+//! the outer cone, and transition linearly in between; the same model is used by OpenAL and
+//! Web Audio API.
+
+use std::f32::consts::TAU;
+
+use irt_lin_alg::na::Vector3;
+
+/// Directivity cone of a source, centered around the direction the source is facing.
+///
+/// Angles are full apex angles of the cones, in radians.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Cone {
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+    /// Gain applied when the listener is outside the outer cone.
+    pub outer_gain: f32,
+}
+
+impl Default for Cone {
+    /// Omnidirectional cone, i.e. full gain in every direction.
+    fn default() -> Self {
+        Self {
+            inner_angle: TAU,
+            outer_angle: TAU,
+            outer_gain: 0.0,
+        }
+    }
+}
+
+impl Cone {
+    pub fn new(inner_angle: f32, outer_angle: f32, outer_gain: f32) -> Self {
+        Self {
+            inner_angle,
+            outer_angle,
+            outer_gain,
+        }
+    }
+
+    /// Compute the gain for a listener located in `direction` from the source, which is facing
+    /// `forward`. Neither of the vectors has to be normalized.
+    pub fn gain_towards(&self, forward: &Vector3<f32>, direction: &Vector3<f32>) -> f32 {
+        // The listener located at the source position is considered to be in front of it
+        if forward.norm_squared() == 0.0 || direction.norm_squared() == 0.0 {
+            return 1.0;
+        }
+
+        self.gain_at_angle(forward.angle(direction))
+    }
+
+    /// Compute the gain for the given angle between the forward direction of the source and
+    /// the direction to the listener.
+    pub fn gain_at_angle(&self, angle: f32) -> f32 {
+        let inner = self.inner_angle / 2.0;
+        let outer = (self.outer_angle / 2.0).max(inner);
+
+        if angle <= inner {
+            1.0
+        } else if angle >= outer {
+            self.outer_gain
+        } else {
+            let t = (angle - inner) / (outer - inner);
+            1.0 + (self.outer_gain - 1.0) * t
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_omnidirectional_by_default() {
+        let cone = Cone::default();
+
+        assert_eq!(cone.gain_at_angle(0.0), 1.0);
+        assert_eq!(cone.gain_at_angle(PI), 1.0);
+    }
+
+    #[test]
+    fn test_cone_gain() {
+        let cone = Cone::new(FRAC_PI_2, PI, 0.2);
+
+        assert_eq!(cone.gain_at_angle(FRAC_PI_8), 1.0);
+        assert_relative_eq!(cone.gain_at_angle(3.0 * FRAC_PI_8), 0.6);
+        assert_eq!(cone.gain_at_angle(FRAC_PI_2 + 0.1), 0.2);
+    }
+
+    #[test]
+    fn test_gain_towards() {
+        let cone = Cone::new(FRAC_PI_2, PI, 0.0);
+        let forward = Vector3::y();
+
+        assert_eq!(
+            cone.gain_towards(&forward, &Vector3::new(0.0, 2.0, 0.0)),
+            1.0
+        );
+        assert_eq!(
+            cone.gain_towards(&forward, &Vector3::new(0.0, -1.0, 0.0)),
+            0.0
+        );
+        assert_eq!(cone.gain_towards(&forward, &Vector3::zeros()), 1.0);
+    }
+}
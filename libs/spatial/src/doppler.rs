@@ -0,0 +1,158 @@
+//! # Doppler effect
+//!
+//! The pitch shift of moving sources and listeners is computed with the same formula as
+//! the one defined by OpenAL 1.1 specification.
+
+use irt_lin_alg::na::Vector3;
+use irt_lin_alg::Point3;
+
+/// Upper bound of the computed factor, which the sources approaching the listener at
+/// the speed of sound (or faster) are limited to.
+pub const MAX_FACTOR: f32 = 16.0;
+
+/// Doppler effect settings of a scene.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Doppler {
+    /// Speed of sound, in units of distance per second; this should match the units used for
+    /// the positions and velocities in the scene.
+    pub speed_of_sound: f32,
+    /// Exaggerates or reduces the effect; zero disables it altogether.
+    pub scale: f32,
+}
+
+impl Default for Doppler {
+    fn default() -> Self {
+        Self {
+            // In dry air at 20 °C, given the distances are in meters
+            speed_of_sound: 343.3,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Position and velocity of a moving object.
+#[derive(Debug, Clone, Copy)]
+pub struct Motion {
+    pub position: Point3,
+    pub velocity: Vector3<f32>,
+}
+
+impl Doppler {
+    /// Compute the factor the frequency of the `source` is multiplied by, as heard by
+    /// the `listener`.
+    ///
+    /// Values greater than one mean the pitch goes up, i.e. the source and the listener are
+    /// approaching each other.
+    pub fn factor(&self, source: Motion, listener: Motion) -> f32 {
+        let source_to_listener = listener.position - source.position;
+        let distance = source_to_listener.norm();
+
+        if self.scale == 0.0 || distance == 0.0 || self.speed_of_sound <= 0.0 {
+            return 1.0;
+        }
+
+        // Velocities of both, projected onto the line between them; the speeds are capped
+        // so that neither of them breaks the sound barrier
+        let limit = self.speed_of_sound / self.scale;
+        let listener_speed = (source_to_listener.dot(&listener.velocity) / distance).min(limit);
+        let source_speed = (source_to_listener.dot(&source.velocity) / distance).min(limit);
+
+        let numerator = self.speed_of_sound - self.scale * listener_speed;
+        let denominator = self.speed_of_sound - self.scale * source_speed;
+
+        let factor = numerator / denominator;
+
+        if factor.is_nan() {
+            return 1.0;
+        }
+
+        // The source approaching at the speed of sound would make the pitch infinitely high
+        factor.clamp(0.0, MAX_FACTOR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn motion(position: [f32; 3], velocity: [f32; 3]) -> Motion {
+        Motion {
+            position: position.into(),
+            velocity: velocity.into(),
+        }
+    }
+
+    const DOPPLER: Doppler = Doppler {
+        speed_of_sound: 340.0,
+        scale: 1.0,
+    };
+
+    #[test]
+    fn test_static_objects() {
+        let factor = DOPPLER.factor(
+            motion([0.0, 10.0, 0.0], [0.0; 3]),
+            motion([0.0; 3], [0.0; 3]),
+        );
+
+        assert_eq!(factor, 1.0);
+    }
+
+    #[test]
+    fn test_approaching_source() {
+        let factor = DOPPLER.factor(
+            motion([0.0, 10.0, 0.0], [0.0, -34.0, 0.0]),
+            motion([0.0; 3], [0.0; 3]),
+        );
+
+        assert_relative_eq!(factor, 340.0 / 306.0);
+    }
+
+    #[test]
+    fn test_receding_listener() {
+        let factor = DOPPLER.factor(
+            motion([0.0, 10.0, 0.0], [0.0; 3]),
+            motion([0.0; 3], [0.0, -34.0, 0.0]),
+        );
+
+        assert_relative_eq!(factor, 306.0 / 340.0);
+    }
+
+    #[test]
+    fn test_perpendicular_motion() {
+        let factor = DOPPLER.factor(
+            motion([0.0, 10.0, 0.0], [5.0, 0.0, 0.0]),
+            motion([0.0; 3], [0.0, 0.0, 3.0]),
+        );
+
+        assert_eq!(factor, 1.0);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let doppler = Doppler {
+            scale: 0.0,
+            ..DOPPLER
+        };
+
+        let factor = doppler.factor(
+            motion([0.0, 10.0, 0.0], [0.0, -34.0, 0.0]),
+            motion([0.0; 3], [0.0; 3]),
+        );
+
+        assert_eq!(factor, 1.0);
+    }
+
+    #[test]
+    fn test_supersonic_source_is_capped() {
+        let factor = DOPPLER.factor(
+            motion([0.0, 10.0, 0.0], [0.0, -1000.0, 0.0]),
+            motion([0.0; 3], [0.0; 3]),
+        );
+
+        assert_eq!(factor, MAX_FACTOR);
+    }
+}
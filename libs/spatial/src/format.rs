@@ -0,0 +1,614 @@
+//! # Scene description format
+//!
+//! Available with `serde` feature enabled, along with the serialization of all the scene
+//! definitions.
+//!
+//! Scenes can be stored in JSON or TOML files, which share the same versioned structure.
+//! Every attribute, except for the source position and the group identifier, may be omitted,
+//! in which case the default value is used:
+//!
+//! ```toml
+//! version = 1
+//!
+//! [doppler]
+//! speed_of_sound = 343.3
+//! scale = 1.0
+//!
+//! [[groups]]
+//! id = 0
+//! name = "stage"
+//! translation = [0.0, 5.0, 0.0]
+//! rotation = [0.0, 0.0, 0.0, 1.0] # quaternion as [x, y, z, w]
+//! scale = 1.0
+//! gain = 1.0
+//! muted = false
+//!
+//! [[sources]]
+//! id = 0
+//! group = 0 # id of the group, if the source belongs to any
+//! name = "vocals"
+//! position = [0.0, 1.0, 0.0]
+//! distance_gain = 1.0
+//! orientation = [0.0, 0.0, 0.0, 1.0]
+//! velocity = [0.0, 0.0, 0.0]
+//! attenuation = { model = "inverse", reference_distance = 1.0, max_distance = 100.0, rolloff = 1.0 }
+//! cone = { inner_angle = 1.57, outer_angle = 3.14, outer_gain = 0.2 }
+//! ```
+//!
+//! Custom attenuation curves are given as `model = { custom = [[distance, gain], ...] }`.
+//!
+//! Source identifiers are optional; the sources without them are assigned the identifiers
+//! following the largest given one.
+//!
+//! Loaded scenes are validated, and the errors point at the offending source or group by its
+//! index in the file, as well as its name, if any.
+
+use std::f32::consts::TAU;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use irt_lin_alg::na::{Translation3, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::group::GroupEntry;
+use crate::{
+    Attenuation, Cone, DistanceModel, Doppler, Group, GroupId, Orientation, Scene, Source,
+    SourceId, Transform,
+};
+
+/// Version of the format written by [save] and [to_string].
+pub const VERSION: u32 = 1;
+
+/// Supported file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LoadError {
+    #[error("cannot read scene file")]
+    Io(
+        #[from]
+        #[source]
+        io::Error,
+    ),
+    #[error("unknown scene file format: expected .json or .toml file")]
+    UnknownFormat,
+    #[error("malformed JSON scene description")]
+    Json(
+        #[from]
+        #[source]
+        serde_json::Error,
+    ),
+    #[error("malformed TOML scene description")]
+    Toml(
+        #[from]
+        #[source]
+        toml::de::Error,
+    ),
+    #[error("unsupported scene format version {0} (expected {VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("invalid scene description")]
+    Invalid(
+        #[from]
+        #[source]
+        ValidationError,
+    ),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SaveError {
+    #[error("cannot write scene file")]
+    Io(
+        #[from]
+        #[source]
+        io::Error,
+    ),
+    #[error("unknown scene file format: expected .json or .toml file")]
+    UnknownFormat,
+    #[error("cannot serialize scene to JSON")]
+    Json(
+        #[from]
+        #[source]
+        serde_json::Error,
+    ),
+    #[error("cannot serialize scene to TOML")]
+    Toml(
+        #[from]
+        #[source]
+        toml::ser::Error,
+    ),
+}
+
+/// Error found in an otherwise well-formed scene description.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{subject}: {problem}")]
+pub struct ValidationError {
+    pub subject: Subject,
+    pub problem: Problem,
+}
+
+/// Part of the scene description a [ValidationError] refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Subject {
+    /// Scene-wide settings.
+    Scene,
+    /// Source at `index` in the list of sources.
+    Source { index: usize, name: Option<String> },
+    /// Group at `index` in the list of groups.
+    Group { index: usize, name: Option<String> },
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum Problem {
+    #[error("`{0}` must be a finite number")]
+    NotFinite(&'static str),
+    #[error("`{0}` must not be negative")]
+    Negative(&'static str),
+    #[error("`{0}` must be positive")]
+    NotPositive(&'static str),
+    #[error("`{0}` must be a unit quaternion")]
+    NotNormalized(&'static str),
+    #[error("`{0}` must be in range [0, 2π]")]
+    AngleOutOfRange(&'static str),
+    #[error("`{0}` must not be less than `{1}`")]
+    LessThan(&'static str, &'static str),
+    #[error("duplicate id {0}")]
+    DuplicateId(u64),
+    #[error("no group with id {0}")]
+    UnknownGroup(GroupId),
+    #[error("group is nested into itself")]
+    Cycle,
+}
+
+/// Serialized form of [Scene].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SceneRepr {
+    #[serde(default)]
+    doppler: Doppler,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<GroupRecord>,
+    #[serde(default)]
+    sources: Vec<SourceRecord>,
+}
+
+/// Serialized form of [Group], which spells out the parts of the transform.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GroupRepr {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default = "Vector3::zeros")]
+    translation: Vector3<f32>,
+    #[serde(default = "Orientation::identity")]
+    rotation: Orientation,
+    #[serde(default = "unit")]
+    scale: f32,
+    #[serde(default = "unit")]
+    gain: f32,
+    #[serde(default)]
+    muted: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GroupRecord {
+    id: GroupId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<GroupId>,
+    #[serde(flatten)]
+    group: Group,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SourceRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<SourceId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<GroupId>,
+    #[serde(flatten)]
+    source: Source,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SceneFile {
+    version: u32,
+    #[serde(flatten)]
+    scene: SceneRepr,
+}
+
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: u32,
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, index, name) = match self {
+            Subject::Scene => return write!(f, "scene"),
+            Subject::Source { index, name } => ("sources", index, name),
+            Subject::Group { index, name } => ("groups", index, name),
+        };
+
+        write!(f, "{kind}[{index}]")?;
+
+        if let Some(name) = name {
+            write!(f, " ({name:?})")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Format {
+    /// Guess the format by the file extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Load the scene from a file; the format is chosen by the file extension.
+pub fn load(path: impl AsRef<Path>) -> Result<Scene, LoadError> {
+    let format = Format::from_path(&path).ok_or(LoadError::UnknownFormat)?;
+    let text = fs::read_to_string(path)?;
+
+    from_str(&text, format)
+}
+
+/// Save the scene into a file; the format is chosen by the file extension.
+pub fn save(scene: &Scene, path: impl AsRef<Path>) -> Result<(), SaveError> {
+    let format = Format::from_path(&path).ok_or(SaveError::UnknownFormat)?;
+    let text = to_string(scene, format)?;
+    fs::write(path, text)?;
+
+    Ok(())
+}
+
+/// Parse and validate the scene description.
+pub fn from_str(text: &str, format: Format) -> Result<Scene, LoadError> {
+    // The version is checked first, since the rest of the description may not make sense
+    // in case of a mismatch
+    let probe: VersionProbe = parse(text, format)?;
+
+    if probe.version != VERSION {
+        return Err(LoadError::UnsupportedVersion(probe.version));
+    }
+
+    let file: SceneFile = parse(text, format)?;
+
+    Ok(file.scene.try_into()?)
+}
+
+/// Serialize the scene description.
+pub fn to_string(scene: &Scene, format: Format) -> Result<String, SaveError> {
+    let file = SceneFile {
+        version: VERSION,
+        scene: scene.clone().into(),
+    };
+
+    let text = match format {
+        Format::Json => serde_json::to_string_pretty(&file)?,
+        Format::Toml => toml::to_string_pretty(&file)?,
+    };
+
+    Ok(text)
+}
+
+/// Check that the scene, which may have been built in code, can be saved and loaded back.
+pub fn validate(scene: &Scene) -> Result<(), ValidationError> {
+    Scene::try_from(SceneRepr::from(scene.clone())).map(|_| ())
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(text: &str, format: Format) -> Result<T, LoadError> {
+    let value = match format {
+        Format::Json => serde_json::from_str(text)?,
+        Format::Toml => toml::from_str(text)?,
+    };
+
+    Ok(value)
+}
+
+impl From<GroupRepr> for Group {
+    fn from(value: GroupRepr) -> Self {
+        Self {
+            name: value.name,
+            transform: Transform::from_parts(
+                Translation3::from(value.translation),
+                value.rotation,
+                value.scale,
+            ),
+            gain: value.gain,
+            muted: value.muted,
+        }
+    }
+}
+
+impl From<Group> for GroupRepr {
+    fn from(value: Group) -> Self {
+        Self {
+            name: value.name,
+            translation: value.transform.isometry.translation.vector,
+            rotation: value.transform.isometry.rotation,
+            scale: value.transform.scaling(),
+            gain: value.gain,
+            muted: value.muted,
+        }
+    }
+}
+
+fn unit() -> f32 {
+    1.0
+}
+
+impl From<Scene> for SceneRepr {
+    fn from(value: Scene) -> Self {
+        let groups = value
+            .groups
+            .iter()
+            .map(|(&id, entry)| GroupRecord {
+                id,
+                parent: entry.parent,
+                group: entry.group.clone(),
+            })
+            .collect();
+
+        let sources = value
+            .ids
+            .iter()
+            .zip(value.sources)
+            .map(|(&id, source)| SourceRecord {
+                id: Some(id),
+                group: value.memberships.get(&id).copied(),
+                source: source.authored(),
+            })
+            .collect();
+
+        Self {
+            doppler: value.doppler,
+            groups,
+            sources,
+        }
+    }
+}
+
+impl TryFrom<SceneRepr> for Scene {
+    type Error = ValidationError;
+
+    fn try_from(value: SceneRepr) -> Result<Self, Self::Error> {
+        let mut scene = Scene::new(vec![]);
+
+        validate_doppler(&value.doppler).map_err(|problem| ValidationError {
+            subject: Subject::Scene,
+            problem,
+        })?;
+        scene.doppler = value.doppler;
+
+        let group_subject = |index: usize, group: &Group| Subject::Group {
+            index,
+            name: group.name.clone(),
+        };
+
+        for (index, record) in value.groups.iter().enumerate() {
+            let check = || {
+                validate_group(&record.group)?;
+
+                if scene.groups.contains_key(&record.id) {
+                    return Err(Problem::DuplicateId(record.id.0));
+                }
+
+                Ok(())
+            };
+
+            check().map_err(|problem| ValidationError {
+                subject: group_subject(index, &record.group),
+                problem,
+            })?;
+
+            scene.groups.insert(
+                record.id,
+                GroupEntry {
+                    group: record.group.clone(),
+                    parent: record.parent,
+                },
+            );
+            scene.next_group_id = scene.next_group_id.max(record.id.0 + 1);
+        }
+
+        for (index, record) in value.groups.iter().enumerate() {
+            check_ancestry(&scene, record).map_err(|problem| ValidationError {
+                subject: group_subject(index, &record.group),
+                problem,
+            })?;
+        }
+
+        let next_free_id = value
+            .sources
+            .iter()
+            .filter_map(|record| record.id)
+            .map(|id| id.0 + 1)
+            .max()
+            .unwrap_or(0);
+        scene.next_id = next_free_id;
+
+        for (index, record) in value.sources.into_iter().enumerate() {
+            let id = record.id.unwrap_or_else(|| {
+                let id = SourceId(scene.next_id);
+                scene.next_id += 1;
+                id
+            });
+
+            let check = || {
+                validate_source(&record.source)?;
+
+                if scene.source(id).is_some() {
+                    return Err(Problem::DuplicateId(id.0));
+                }
+
+                match record.group {
+                    Some(group) if !scene.groups.contains_key(&group) => {
+                        Err(Problem::UnknownGroup(group))
+                    }
+                    _ => Ok(()),
+                }
+            };
+
+            check().map_err(|problem| ValidationError {
+                subject: Subject::Source {
+                    index,
+                    name: record.source.name.clone(),
+                },
+                problem,
+            })?;
+
+            if let Some(group) = record.group {
+                scene.memberships.insert(id, group);
+            }
+
+            scene.put_source(id, record.source);
+        }
+
+        Ok(scene)
+    }
+}
+
+fn check_ancestry(scene: &Scene, record: &GroupRecord) -> Result<(), Problem> {
+    let mut parent = record.parent;
+    let mut depth = 0;
+
+    while let Some(id) = parent {
+        let entry = scene.groups.get(&id).ok_or(Problem::UnknownGroup(id))?;
+
+        // Any chain longer than the number of groups must have looped somewhere
+        depth += 1;
+        if id == record.id || depth > scene.groups.len() {
+            return Err(Problem::Cycle);
+        }
+
+        parent = entry.parent;
+    }
+
+    Ok(())
+}
+
+fn validate_doppler(doppler: &Doppler) -> Result<(), Problem> {
+    positive("doppler.speed_of_sound", doppler.speed_of_sound)?;
+    non_negative("doppler.scale", doppler.scale)
+}
+
+fn validate_group(group: &Group) -> Result<(), Problem> {
+    let translation = &group.transform.isometry.translation.vector;
+
+    if !translation.iter().all(|v| v.is_finite()) {
+        return Err(Problem::NotFinite("translation"));
+    }
+
+    unit_quaternion("rotation", &group.transform.isometry.rotation)?;
+    positive("scale", group.transform.scaling())?;
+    non_negative("gain", group.gain)
+}
+
+fn validate_source(source: &Source) -> Result<(), Problem> {
+    if !source.position.iter().all(|v| v.is_finite()) {
+        return Err(Problem::NotFinite("position"));
+    }
+
+    if !source.velocity.iter().all(|v| v.is_finite()) {
+        return Err(Problem::NotFinite("velocity"));
+    }
+
+    non_negative("distance_gain", source.distance_gain())?;
+    unit_quaternion("orientation", &source.orientation)?;
+    validate_attenuation(&source.attenuation)?;
+    validate_cone(&source.cone)
+}
+
+fn validate_attenuation(attenuation: &Attenuation) -> Result<(), Problem> {
+    non_negative(
+        "attenuation.reference_distance",
+        attenuation.reference_distance,
+    )?;
+    non_negative("attenuation.max_distance", attenuation.max_distance)?;
+    non_negative("attenuation.rolloff", attenuation.rolloff)?;
+
+    if attenuation.max_distance < attenuation.reference_distance {
+        return Err(Problem::LessThan(
+            "attenuation.max_distance",
+            "attenuation.reference_distance",
+        ));
+    }
+
+    if let DistanceModel::Custom(curve) = &attenuation.model {
+        for &(distance, gain) in curve.points() {
+            if !distance.is_finite() {
+                return Err(Problem::NotFinite("attenuation.model.custom"));
+            }
+
+            non_negative("attenuation.model.custom", gain)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_cone(cone: &Cone) -> Result<(), Problem> {
+    for (name, angle) in [
+        ("cone.inner_angle", cone.inner_angle),
+        ("cone.outer_angle", cone.outer_angle),
+    ] {
+        if !(0.0..=TAU).contains(&angle) {
+            return Err(Problem::AngleOutOfRange(name));
+        }
+    }
+
+    if cone.outer_angle < cone.inner_angle {
+        return Err(Problem::LessThan("cone.outer_angle", "cone.inner_angle"));
+    }
+
+    non_negative("cone.outer_gain", cone.outer_gain)
+}
+
+fn unit_quaternion(name: &'static str, orientation: &Orientation) -> Result<(), Problem> {
+    const TOLERANCE: f32 = 1e-3;
+
+    let coords = &orientation.coords;
+
+    if !coords.iter().all(|v| v.is_finite()) {
+        return Err(Problem::NotFinite(name));
+    }
+
+    if (coords.norm() - 1.0).abs() > TOLERANCE {
+        return Err(Problem::NotNormalized(name));
+    }
+
+    Ok(())
+}
+
+fn non_negative(name: &'static str, value: f32) -> Result<(), Problem> {
+    if !value.is_finite() {
+        return Err(Problem::NotFinite(name));
+    }
+
+    if value < 0.0 {
+        return Err(Problem::Negative(name));
+    }
+
+    Ok(())
+}
+
+fn positive(name: &'static str, value: f32) -> Result<(), Problem> {
+    non_negative(name, value)?;
+
+    if value == 0.0 {
+        return Err(Problem::NotPositive(name));
+    }
+
+    Ok(())
+}
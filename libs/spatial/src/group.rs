@@ -0,0 +1,272 @@
+//! # Source groups
+//!
+//! Groups let a number of sources be transformed, muted or attenuated as a unit, e.g. all
+//! the pieces of a drum kit. Groups may be nested; the position of a grouped source is given
+//! relative to its group, and the transforms of all the enclosing groups are resolved into
+//! the world coordinates before the source is perceived by the listener.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use irt_lin_alg::Transform;
+
+use crate::{Scene, Source, SourceId, UnknownSourceError};
+
+/// Stable identifier of a [Group] within a [Scene].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct GroupId(pub(crate) u64);
+
+/// Group of sources sharing a transform and a gain.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "crate::format::GroupRepr", into = "crate::format::GroupRepr")
+)]
+pub struct Group {
+    pub name: Option<String>,
+    /// Transform from the group coordinates to the ones of its parent (or the world, if
+    /// the group has no parent).
+    pub transform: Transform,
+    /// Gain applied to all the sources of the group, on top of their own gain.
+    pub gain: f32,
+    /// Muted group silences all its sources, including the ones in nested groups.
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GroupEntry {
+    pub(crate) group: Group,
+    pub(crate) parent: Option<GroupId>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("no group with id {0} in the scene")]
+pub struct UnknownGroupError(pub GroupId);
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupError {
+    #[error(transparent)]
+    UnknownSource(#[from] UnknownSourceError),
+    #[error(transparent)]
+    UnknownGroup(#[from] UnknownGroupError),
+}
+
+impl GroupId {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for GroupId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Self {
+            name: None,
+            transform: Transform::identity(),
+            gain: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl Group {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            transform,
+            ..Default::default()
+        }
+    }
+
+    pub fn named(name: impl Into<String>, transform: Transform) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::new(transform)
+        }
+    }
+}
+
+impl Scene {
+    /// Add a group to the scene, optionally nested into the `parent` group.
+    pub fn add_group(
+        &mut self,
+        group: Group,
+        parent: Option<GroupId>,
+    ) -> Result<GroupId, UnknownGroupError> {
+        if let Some(parent) = parent {
+            self.group(parent).ok_or(UnknownGroupError(parent))?;
+        }
+
+        let id = GroupId(self.next_group_id);
+        self.next_group_id += 1;
+        self.groups.insert(id, GroupEntry { group, parent });
+
+        Ok(id)
+    }
+
+    /// Remove a group from the scene; its sources and nested groups are moved to the parent
+    /// of the removed group, keeping their coordinates as is.
+    pub fn remove_group(&mut self, id: GroupId) -> Option<Group> {
+        let GroupEntry { group, parent } = self.groups.remove(&id)?;
+
+        for entry in self.groups.values_mut() {
+            if entry.parent == Some(id) {
+                entry.parent = parent;
+            }
+        }
+
+        match parent {
+            Some(parent) => self
+                .memberships
+                .values_mut()
+                .filter(|group| **group == id)
+                .for_each(|group| *group = parent),
+            None => self.memberships.retain(|_, group| *group != id),
+        }
+
+        Some(group)
+    }
+
+    pub fn group(&self, id: GroupId) -> Option<&Group> {
+        self.groups.get(&id).map(|entry| &entry.group)
+    }
+
+    pub fn group_mut(&mut self, id: GroupId) -> Option<&mut Group> {
+        self.groups.get_mut(&id).map(|entry| &mut entry.group)
+    }
+
+    pub fn group_parent(&self, id: GroupId) -> Option<GroupId> {
+        self.groups.get(&id).and_then(|entry| entry.parent)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (GroupId, &Group)> {
+        self.groups.iter().map(|(id, entry)| (*id, &entry.group))
+    }
+
+    /// Find the first group with the given name.
+    pub fn find_group(&self, name: &str) -> Option<GroupId> {
+        self.groups()
+            .find(|(_, group)| group.name.as_deref() == Some(name))
+            .map(|(id, _)| id)
+    }
+
+    /// Add a source to the scene as a member of `group`; its position is relative to the group.
+    pub fn add_grouped_source(
+        &mut self,
+        source: Source,
+        group: GroupId,
+    ) -> Result<SourceId, UnknownGroupError> {
+        self.group(group).ok_or(UnknownGroupError(group))?;
+
+        let id = self.add_source(source);
+        self.memberships.insert(id, group);
+
+        Ok(id)
+    }
+
+    /// Move the source into `group`, or out of any group if [None] is given.
+    ///
+    /// The coordinates of the source are kept as is, so they are now interpreted relative to
+    /// the new group.
+    pub fn set_source_group(
+        &mut self,
+        source: SourceId,
+        group: Option<GroupId>,
+    ) -> Result<(), GroupError> {
+        self.source(source).ok_or(UnknownSourceError(source))?;
+
+        match group {
+            Some(group) => {
+                self.group(group).ok_or(UnknownGroupError(group))?;
+                self.memberships.insert(source, group);
+            }
+            None => {
+                self.memberships.remove(&source);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn source_group(&self, source: SourceId) -> Option<GroupId> {
+        self.memberships.get(&source).copied()
+    }
+
+    /// Transform from the coordinates of the group to the world coordinates, which combines
+    /// the transforms of the group and all of its ancestors.
+    pub fn world_transform(&self, id: GroupId) -> Option<Transform> {
+        let mut transform = self.groups.get(&id)?.group.transform;
+        let mut parent = self.group_parent(id);
+
+        while let Some(id) = parent {
+            let entry = &self.groups[&id];
+            transform = entry.group.transform * transform;
+            parent = entry.parent;
+        }
+
+        Some(transform)
+    }
+
+    /// Gain of the group combined with the gains of all of its ancestors; zero if any of them
+    /// is muted.
+    pub fn world_gain(&self, id: GroupId) -> Option<f32> {
+        let mut gain = 1.0;
+        let mut current = Some(id);
+
+        while let Some(id) = current {
+            let entry = self.groups.get(&id)?;
+
+            if entry.group.muted {
+                return Some(0.0);
+            }
+
+            gain *= entry.group.gain;
+            current = entry.parent;
+        }
+
+        Some(gain)
+    }
+
+    /// Flatten the scene, resolving the groups of all the sources into the world coordinates.
+    ///
+    /// The resulting scene keeps the source identifiers, but doesn't contain any groups.
+    pub fn resolved(&self) -> Scene {
+        self.map_sources(|id, source| self.world_source(id, source).into_owned())
+    }
+
+    /// Resolve the `source`, identified by `id`, into the world coordinates.
+    pub(crate) fn world_source<'a>(&self, id: SourceId, source: &'a Source) -> Cow<'a, Source> {
+        let Some((transform, gain)) = self.group_frame(id) else {
+            return Cow::Borrowed(source);
+        };
+
+        let mut world = source.relocated(transform.transform_point(&source.position));
+        world.orientation = transform.isometry.rotation * source.orientation;
+        world.velocity = transform.transform_vector(&source.velocity);
+        world.distance_gain.0 *= gain;
+
+        Cow::Owned(world)
+    }
+
+    /// World transform and gain of the group the source belongs to, if any.
+    pub(crate) fn group_frame(&self, id: SourceId) -> Option<(Transform, f32)> {
+        self.source_group(id).map(|group| self.world_frame(group))
+    }
+
+    /// World transform and gain of the `group`, which must be in the scene.
+    pub(crate) fn world_frame(&self, group: GroupId) -> (Transform, f32) {
+        let transform = self
+            .world_transform(group)
+            .expect("group must be in the scene");
+        let gain = self.world_gain(group).expect("group must be in the scene");
+
+        (transform, gain)
+    }
+}
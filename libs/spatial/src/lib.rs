@@ -1,11 +1,67 @@
-pub use irt_lin_alg::{na, Orientation, Point3};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
 
-#[derive(Debug, PartialEq)]
+use irt_lin_alg::na::Vector3;
+
+pub use attenuation::{Attenuation, Curve, DistanceModel};
+pub use directivity::Cone;
+pub use doppler::{Doppler, Motion};
+pub use group::{Group, GroupError, GroupId, UnknownGroupError};
+pub use irt_lin_alg::{na, Orientation, Point3, Spherical, Transform};
+
+mod attenuation;
+mod baseline;
+mod directivity;
+mod doppler;
+#[cfg(feature = "serde")]
+pub mod format;
+mod group;
+mod smoothing;
+
+/// Stable identifier of a source within a [Scene].
+///
+/// Identifiers are assigned by the scene when a source is added and never reused by that scene,
+/// so they remain valid (and keep pointing to the same source) while other sources come and go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct SourceId(u64);
+
+/// Collection of sources, which can be serialized in the format described in `format` module,
+/// with `serde` feature enabled.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "format::SceneRepr", into = "format::SceneRepr")
+)]
 pub struct Scene {
+    // Both vectors are kept in sync and sorted by id, which is monotonically increasing
+    ids: Vec<SourceId>,
     sources: Vec<Source>,
+    next_id: u64,
+    doppler: Doppler,
+    groups: BTreeMap<GroupId, group::GroupEntry>,
+    memberships: BTreeMap<SourceId, GroupId>,
+    next_group_id: u64,
 }
 
-#[derive(Debug, PartialEq)]
+/// A single change made to a [Scene], as reported to [Renderer::render_changes].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneChange {
+    Added(SourceId),
+    Removed(SourceId),
+    Updated(SourceId),
+}
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("no source with id {0} in the scene")]
+pub struct UnknownSourceError(pub SourceId);
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 struct DistanceGain(f32);
 
 impl Default for DistanceGain {
@@ -14,44 +70,115 @@ impl Default for DistanceGain {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Source {
     position: Point3,
+    #[cfg_attr(feature = "serde", serde(default))]
     distance_gain: DistanceGain,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    name: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    attenuation: Attenuation,
+    #[cfg_attr(feature = "serde", serde(default = "Orientation::identity"))]
+    orientation: Orientation,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cone: Cone,
+    #[cfg_attr(feature = "serde", serde(default = "Vector3::zeros"))]
+    velocity: Vector3<f32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    perception: Perception,
+}
+
+/// Values computed for a source when it's perceived by a [Listener].
+///
+/// Sources that haven't been perceived, e.g. the ones in a world-space scene, carry neutral values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perception {
+    distance_attenuation: f32,
+    directivity_gain: f32,
+    doppler_factor: f32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Listener {
+    #[cfg_attr(feature = "serde", serde(default = "Point3::origin"))]
     location: Point3,
     orientation: Orientation,
+    #[cfg_attr(feature = "serde", serde(default = "Vector3::zeros"))]
+    velocity: Vector3<f32>,
 }
 
 pub trait Renderer {
     fn render_scene(&mut self, scene: &Scene);
+
+    /// Render the scene after a single source has changed.
+    ///
+    /// `scene` is the complete, up-to-date scene, so renderers that can only consume the scene
+    /// as a whole may keep the default implementation, which simply calls [render_scene].
+    /// Renderers that are able to update individual objects may override this to apply
+    /// the `change` alone.
+    ///
+    /// [render_scene]: Renderer::render_scene
+    fn render_changes(&mut self, scene: &Scene, change: SceneChange) {
+        let _ = change;
+        self.render_scene(scene);
+    }
 }
 
 pub struct Soundscape<T: Renderer> {
     listener: Listener,
     scene: Scene,
+    perceived_scene: Scene,
     renderer: T,
+    smoothing: Option<Duration>,
+    transition: Option<smoothing::Transition>,
 }
 
 pub struct SourceBuilder {
-    position: Point3,
-    distance_gain: DistanceGain,
+    source: Source,
 }
 
 impl SourceBuilder {
     pub fn distance_gain(mut self, value: f32) -> Self {
-        self.distance_gain = DistanceGain(value);
+        self.source.distance_gain = DistanceGain(value);
+        self
+    }
+
+    /// Human-readable name of the source; it doesn't have to be unique.
+    pub fn name(mut self, value: impl Into<String>) -> Self {
+        self.source.name = Some(value.into());
+        self
+    }
+
+    pub fn attenuation(mut self, value: impl Into<Attenuation>) -> Self {
+        self.source.attenuation = value.into();
+        self
+    }
+
+    /// Orientation of the source; see [Source::forward] for the direction it faces.
+    pub fn orientation(mut self, value: Orientation) -> Self {
+        self.source.orientation = value;
+        self
+    }
+
+    pub fn cone(mut self, value: Cone) -> Self {
+        self.source.cone = value;
+        self
+    }
+
+    /// Velocity of the source, in units of distance per second.
+    pub fn velocity(mut self, value: Vector3<f32>) -> Self {
+        self.source.velocity = value;
         self
     }
 
     pub fn build(self) -> Source {
-        Source {
-            position: self.position,
-            distance_gain: self.distance_gain,
-        }
+        self.source
     }
 }
 
@@ -60,80 +187,473 @@ impl Source {
         Self {
             position: position.into(),
             distance_gain: Default::default(),
+            name: None,
+            attenuation: Default::default(),
+            orientation: Orientation::identity(),
+            cone: Default::default(),
+            velocity: Vector3::zeros(),
+            perception: Default::default(),
         }
     }
 
     pub fn with_location(position: impl Into<Point3>) -> SourceBuilder {
         SourceBuilder {
-            position: position.into(),
-            distance_gain: Default::default(),
+            source: Self::new(position),
         }
     }
 
     pub fn perceived_from(&self, orientation: &Orientation) -> Self {
         let position = orientation.transform_point(&self.position);
-        Self::new(position)
+
+        Self {
+            orientation: orientation * self.orientation,
+            velocity: orientation * self.velocity,
+            ..self.relocated(position)
+        }
+    }
+
+    /// Returns a copy of the source placed at `position`; all other attributes are kept as is.
+    pub fn relocated(&self, position: impl Into<Point3>) -> Self {
+        Self {
+            position: position.into(),
+            ..self.clone()
+        }
     }
 
     pub fn location(&self) -> Point3 {
         self.position
     }
 
+    /// Location of the source in spherical coordinates.
+    ///
+    /// For the sources of a scene perceived by a [Listener], these are the azimuth, elevation and
+    /// distance relative to the listener's head.
+    pub fn spherical_location(&self) -> Spherical {
+        Spherical::from_point(&self.position)
+    }
+
+    pub fn set_location(&mut self, position: impl Into<Point3>) {
+        self.position = position.into();
+    }
+
     pub fn distance_gain(&self) -> f32 {
         self.distance_gain.0
     }
+
+    pub fn set_distance_gain(&mut self, value: f32) {
+        self.distance_gain = DistanceGain(value);
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    pub fn attenuation(&self) -> &Attenuation {
+        &self.attenuation
+    }
+
+    pub fn set_attenuation(&mut self, value: impl Into<Attenuation>) {
+        self.attenuation = value.into();
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn set_orientation(&mut self, value: Orientation) {
+        self.orientation = value;
+    }
+
+    /// The direction the source is facing.
+    ///
+    /// An unrotated source faces the positive direction of y-axis, same as the listener does.
+    pub fn forward(&self) -> Vector3<f32> {
+        self.orientation * Vector3::y()
+    }
+
+    pub fn cone(&self) -> &Cone {
+        &self.cone
+    }
+
+    pub fn set_cone(&mut self, value: Cone) {
+        self.cone = value;
+    }
+
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    pub fn set_velocity(&mut self, value: Vector3<f32>) {
+        self.velocity = value;
+    }
+
+    /// Values computed by [Listener::perceived_scene] for this source.
+    pub fn perception(&self) -> &Perception {
+        &self.perception
+    }
+
+    /// The resulting gain the source shall be rendered with, which combines the gain of the
+    /// source itself with the perceived attenuation and directivity.
+    pub fn gain(&self) -> f32 {
+        self.distance_gain()
+            * self.perception.distance_attenuation
+            * self.perception.directivity_gain
+    }
+
+    fn motion(&self) -> Motion {
+        Motion {
+            position: self.position,
+            velocity: self.velocity,
+        }
+    }
+
+    /// Returns a copy of the source with the values computed by perception reset,
+    /// which leaves only the attributes the source has been authored with.
+    pub fn authored(&self) -> Self {
+        Self {
+            perception: Default::default(),
+            ..self.clone()
+        }
+    }
 }
 
-impl Listener {
-    pub fn new(orientation: Orientation) -> Self {
+impl Default for Perception {
+    fn default() -> Self {
         Self {
-            location: Point3::origin(),
-            orientation,
+            distance_attenuation: 1.0,
+            directivity_gain: 1.0,
+            doppler_factor: 1.0,
         }
     }
+}
+
+impl Perception {
+    /// Gain computed by the [Attenuation] model of the source for the listener-to-source distance.
+    pub fn distance_attenuation(&self) -> f32 {
+        self.distance_attenuation
+    }
+
+    /// Gain computed by the [Cone] of the source for the direction the listener is located in.
+    pub fn directivity_gain(&self) -> f32 {
+        self.directivity_gain
+    }
+
+    /// Factor the pitch of the source shall be multiplied by, as computed by the [Doppler]
+    /// settings of the scene for the relative motion of the source and the listener.
+    pub fn doppler_factor(&self) -> f32 {
+        self.doppler_factor
+    }
+}
+
+impl Listener {
+    pub fn new(orientation: Orientation) -> Self {
+        Self::new_with_location(Point3::origin(), orientation)
+    }
 
     pub fn new_with_location(location: Point3, orientation: Orientation) -> Self {
         Self {
             location,
             orientation,
+            velocity: Vector3::zeros(),
         }
     }
 
+    /// Set the velocity of the listener, in units of distance per second.
+    pub fn with_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
     pub fn perceived_scene(&self, scene: &Scene) -> Scene {
-        // TODO(max-khm): This could be potentially optimized with SIMD calculations
-        let sources = scene
-            .sources
-            .iter()
-            .map(|source| source.position - self.location)
-            .map(Point3::from)
-            .map(Source::new)
-            .collect();
-        Scene::new(sources).relative_to(self.orientation.inverse())
+        let mut perceived = Scene::new(Vec::new());
+        self.perceive_into(scene, &mut perceived);
+        perceived
+    }
+
+    /// Perceive the `scene` into `out`, which is overwritten with the same result
+    /// [perceived_scene] would return.
+    ///
+    /// The sources of `out` are updated in place, so once it has been filled, perceiving
+    /// a scene of the same size and attributes again doesn't allocate, apart from resolving
+    /// the groups of a grouped scene; this is the preferred way of rendering the same scene
+    /// repeatedly, e.g. as the listener moves.
+    ///
+    /// [perceived_scene]: Listener::perceived_scene
+    pub fn perceive_into(&self, scene: &Scene, out: &mut Scene) {
+        let inverse = self.orientation.inverse();
+        let listener = self.motion();
+        // Each group is resolved once, however many sources it contains
+        let mut frames = BTreeMap::new();
+
+        out.ids.clone_from(&scene.ids);
+        out.sources.truncate(scene.sources.len());
+
+        for (index, (id, source)) in scene.iter().enumerate() {
+            let group = scene.source_group(id).map(|group| {
+                *frames
+                    .entry(group)
+                    .or_insert_with(|| scene.world_frame(group))
+            });
+
+            match out.sources.get_mut(index) {
+                Some(target) => {
+                    self.perceive_onto(source, group, &inverse, listener, &scene.doppler, target)
+                }
+                None => {
+                    let mut target = source.clone();
+                    self.perceive_onto(
+                        source,
+                        group,
+                        &inverse,
+                        listener,
+                        &scene.doppler,
+                        &mut target,
+                    );
+                    out.sources.push(target);
+                }
+            }
+        }
+
+        out.next_id = scene.next_id;
+        out.doppler.clone_from(&scene.doppler);
+        out.groups.clear();
+        out.memberships.clear();
+        out.next_group_id = 0;
+    }
+
+    /// Perceive a single source of the `scene`, in the same way [perceived_scene] does
+    /// for each source.
+    ///
+    /// [perceived_scene]: Listener::perceived_scene
+    pub fn perceived_source(&self, scene: &Scene, id: SourceId) -> Option<Source> {
+        let source = scene.source(id)?;
+        let mut perceived = source.clone();
+
+        self.perceive_onto(
+            source,
+            scene.group_frame(id),
+            &self.orientation.inverse(),
+            self.motion(),
+            &scene.doppler,
+            &mut perceived,
+        );
+
+        Some(perceived)
+    }
+
+    /// Write the perceived `source`, which belongs to a group with the given world transform
+    /// and gain (if any), into `out`, reusing its allocations.
+    fn perceive_onto(
+        &self,
+        source: &Source,
+        group: Option<(Transform, f32)>,
+        inverse: &Orientation,
+        listener: Motion,
+        doppler: &Doppler,
+        out: &mut Source,
+    ) {
+        let (world, orientation, distance_gain) = match group {
+            Some((transform, gain)) => (
+                Motion {
+                    position: transform.transform_point(&source.position),
+                    velocity: transform.transform_vector(&source.velocity),
+                },
+                transform.isometry.rotation * source.orientation,
+                source.distance_gain.0 * gain,
+            ),
+            None => (source.motion(), source.orientation, source.distance_gain.0),
+        };
+
+        let offset = world.position - self.location.coords;
+        let distance = offset.coords.norm();
+
+        out.position = inverse.transform_point(&offset);
+        out.distance_gain = DistanceGain(distance_gain);
+        out.name.clone_from(&source.name);
+        out.orientation = inverse * orientation;
+        out.cone.clone_from(&source.cone);
+        out.velocity = inverse * world.velocity;
+
+        // Custom curves would be reallocated by cloning, even though they rarely change
+        if out.attenuation != source.attenuation {
+            out.attenuation.clone_from(&source.attenuation);
+        }
+
+        out.perception = Perception {
+            distance_attenuation: source.attenuation.gain_at(distance),
+            directivity_gain: source
+                .cone
+                .gain_towards(&(orientation * Vector3::y()), &-offset.coords),
+            doppler_factor: doppler.factor(world, listener),
+        };
     }
 
     pub fn location(&self) -> Point3 {
         self.location
     }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    fn motion(&self) -> Motion {
+        Motion {
+            position: self.location,
+            velocity: self.velocity,
+        }
+    }
+}
+
+impl SourceId {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for SourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
 }
 
 impl Scene {
+    /// Create a scene out of `sources`; identifiers are assigned in the order of the sources.
     pub fn new(sources: Vec<Source>) -> Self {
-        Self { sources }
+        let ids = (0..sources.len() as u64).map(SourceId).collect();
+        let next_id = sources.len() as u64;
+
+        Self {
+            ids,
+            sources,
+            next_id,
+            doppler: Default::default(),
+            groups: Default::default(),
+            memberships: Default::default(),
+            next_group_id: 0,
+        }
     }
 
+    /// Rotate the scene by `orientation`; the groups are resolved into the world coordinates
+    /// prior to the rotation.
     pub fn relative_to(&self, orientation: Orientation) -> Self {
-        let sources = self
-            .sources
-            .iter()
-            .map(|source| source.perceived_from(&orientation))
-            .collect();
-
-        Self { sources }
+        self.map_sources(|id, source| self.world_source(id, source).perceived_from(&orientation))
     }
 
     pub fn sources(&self) -> &[Source] {
         &self.sources
     }
+
+    pub fn doppler(&self) -> &Doppler {
+        &self.doppler
+    }
+
+    pub fn set_doppler(&mut self, value: Doppler) {
+        self.doppler = value;
+    }
+
+    /// Identifiers of the sources, in the same order as [sources] returns them.
+    ///
+    /// [sources]: Scene::sources
+    pub fn ids(&self) -> &[SourceId] {
+        &self.ids
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SourceId, &Source)> {
+        self.ids.iter().copied().zip(self.sources.iter())
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn source(&self, id: SourceId) -> Option<&Source> {
+        self.index_of(id).map(|index| &self.sources[index])
+    }
+
+    pub fn source_mut(&mut self, id: SourceId) -> Option<&mut Source> {
+        self.index_of(id).map(|index| &mut self.sources[index])
+    }
+
+    /// Find the first source with the given name.
+    pub fn find(&self, name: &str) -> Option<SourceId> {
+        self.iter()
+            .find(|(_, source)| source.name() == Some(name))
+            .map(|(id, _)| id)
+    }
+
+    /// Add a source to the scene and return its newly assigned identifier.
+    pub fn add_source(&mut self, source: Source) -> SourceId {
+        let id = SourceId(self.next_id);
+        self.next_id += 1;
+
+        self.ids.push(id);
+        self.sources.push(source);
+
+        id
+    }
+
+    /// Remove a source from the scene; returns [None] if there was no such source.
+    pub fn remove_source(&mut self, id: SourceId) -> Option<Source> {
+        let index = self.index_of(id)?;
+        self.ids.remove(index);
+        self.memberships.remove(&id);
+
+        Some(self.sources.remove(index))
+    }
+
+    /// Create an ungrouped scene with the same identifiers, but with each source transformed
+    /// by `f`.
+    fn map_sources(&self, mut f: impl FnMut(SourceId, &Source) -> Source) -> Self {
+        Self {
+            ids: self.ids.clone(),
+            sources: self.iter().map(|(id, source)| f(id, source)).collect(),
+            next_id: self.next_id,
+            doppler: self.doppler.clone(),
+            groups: Default::default(),
+            memberships: Default::default(),
+            next_group_id: 0,
+        }
+    }
+
+    /// Put `source` under the given `id`, either replacing the existing source or inserting
+    /// a new one in place, so that identifiers stay sorted.
+    fn put_source(&mut self, id: SourceId, source: Source) {
+        match self.ids.binary_search(&id) {
+            Ok(index) => self.sources[index] = source,
+            Err(index) => {
+                self.ids.insert(index, id);
+                self.sources.insert(index, source);
+                self.next_id = self.next_id.max(id.0 + 1);
+            }
+        }
+    }
+
+    fn index_of(&self, id: SourceId) -> Option<usize> {
+        self.ids.binary_search(&id).ok()
+    }
+}
+
+impl PartialEq for Scene {
+    fn eq(&self, other: &Self) -> bool {
+        // next_id is bookkeeping and doesn't contribute to what the scene contains
+        self.ids == other.ids
+            && self.sources == other.sources
+            && self.doppler == other.doppler
+            && self.groups == other.groups
+            && self.memberships == other.memberships
+    }
 }
 
 impl FromIterator<Source> for Scene {
@@ -156,25 +676,214 @@ impl From<(Point3, Orientation)> for Listener {
 
 impl<T: Renderer> Soundscape<T> {
     pub fn new(initial_scene: Scene, initial_listener: Listener, renderer: T) -> Soundscape<T> {
+        let perceived_scene = initial_listener.perceived_scene(&initial_scene);
+
         let mut instance = Self {
             listener: initial_listener,
             scene: initial_scene,
+            perceived_scene,
             renderer,
+            smoothing: None,
+            transition: None,
         };
 
-        instance.update_scene();
+        instance.renderer.render_scene(&instance.perceived_scene);
 
         instance
     }
 
+    /// Set the new listener pose.
+    ///
+    /// If the smoothing is enabled, the listener only starts moving towards the new pose, which
+    /// is reached as [advance] is called; otherwise the scene is re-rendered immediately.
+    ///
+    /// [advance]: Soundscape::advance
     pub fn set_listener(&mut self, listener: Listener) {
+        if self.smoothing.is_some() {
+            // Any ongoing transition is restarted from wherever the listener is at the moment
+            self.transition = Some(smoothing::Transition::new(self.listener.clone(), listener));
+            return;
+        }
+
+        self.listener = listener;
+        self.update_scene();
+    }
+
+    /// The listener the scene is currently rendered for, which may be in transition
+    /// to the [target_listener].
+    ///
+    /// [target_listener]: Soundscape::target_listener
+    pub fn listener(&self) -> &Listener {
+        &self.listener
+    }
+
+    /// The listener most recently passed to [set_listener].
+    ///
+    /// [set_listener]: Soundscape::set_listener
+    pub fn target_listener(&self) -> &Listener {
+        self.transition
+            .as_ref()
+            .map_or(&self.listener, |transition| transition.target())
+    }
+
+    /// Set the time it takes for the listener to move to a new pose, or disable the smoothing
+    /// with [None] (or zero duration).
+    ///
+    /// Disabling the smoothing during a transition moves the listener to its target right away.
+    pub fn set_listener_smoothing(&mut self, duration: Option<Duration>) {
+        self.smoothing = duration.filter(|duration| !duration.is_zero());
+
+        if self.smoothing.is_none() {
+            if let Some(transition) = self.transition.take() {
+                self.listener = transition.target().clone();
+                self.update_scene();
+            }
+        }
+    }
+
+    pub fn listener_smoothing(&self) -> Option<Duration> {
+        self.smoothing
+    }
+
+    /// Whether the listener has reached its target pose.
+    pub fn is_settled(&self) -> bool {
+        self.transition.is_none()
+    }
+
+    /// Move the listener towards its target pose by the `elapsed` time, rendering the scene
+    /// perceived from the intermediate pose.
+    ///
+    /// This is expected to be called periodically, at the rate the intermediate scenes should
+    /// be rendered at; it does nothing once the listener is settled.
+    pub fn advance(&mut self, elapsed: Duration) {
+        let (Some(transition), Some(duration)) = (&mut self.transition, self.smoothing) else {
+            return;
+        };
+
+        let (listener, finished) = transition.advance(elapsed, duration);
+
+        if finished {
+            self.transition = None;
+        }
+
         self.listener = listener;
         self.update_scene();
     }
 
+    /// The scene in world coordinates, as opposed to the one perceived by the listener.
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    /// Add a new source to the scene, rendering only the new source.
+    pub fn add_source(&mut self, source: Source) -> SourceId {
+        let id = self.scene.add_source(source);
+        self.refresh_source(id, SceneChange::Added(id));
+        id
+    }
+
+    /// Remove a source from the scene; returns [None] if there was no such source.
+    pub fn remove_source(&mut self, id: SourceId) -> Option<Source> {
+        let source = self.scene.remove_source(id)?;
+        self.perceived_scene.remove_source(id);
+        self.renderer
+            .render_changes(&self.perceived_scene, SceneChange::Removed(id));
+
+        Some(source)
+    }
+
+    /// Move a source to a new position, given in world coordinates.
+    pub fn move_source(
+        &mut self,
+        id: SourceId,
+        position: impl Into<Point3>,
+    ) -> Result<(), UnknownSourceError> {
+        self.update_source(id, |source| source.set_location(position))
+    }
+
+    /// Modify any attributes of a source with `f`, then render the updated source.
+    pub fn update_source(
+        &mut self,
+        id: SourceId,
+        f: impl FnOnce(&mut Source),
+    ) -> Result<(), UnknownSourceError> {
+        let source = self.scene.source_mut(id).ok_or(UnknownSourceError(id))?;
+        f(source);
+        self.refresh_source(id, SceneChange::Updated(id));
+
+        Ok(())
+    }
+
+    /// Add a new group to the scene; nothing is rendered until sources are added into it.
+    pub fn add_group(
+        &mut self,
+        group: Group,
+        parent: Option<GroupId>,
+    ) -> Result<GroupId, UnknownGroupError> {
+        self.scene.add_group(group, parent)
+    }
+
+    /// Add a new source into the `group`, rendering only the new source.
+    pub fn add_grouped_source(
+        &mut self,
+        source: Source,
+        group: GroupId,
+    ) -> Result<SourceId, UnknownGroupError> {
+        let id = self.scene.add_grouped_source(source, group)?;
+        self.refresh_source(id, SceneChange::Added(id));
+
+        Ok(id)
+    }
+
+    /// Move a source into another group, or out of any group if [None] is given.
+    pub fn set_source_group(
+        &mut self,
+        id: SourceId,
+        group: Option<GroupId>,
+    ) -> Result<(), GroupError> {
+        self.scene.set_source_group(id, group)?;
+        self.refresh_source(id, SceneChange::Updated(id));
+
+        Ok(())
+    }
+
+    /// Modify the group with `f`, e.g. to move, mute or attenuate it as a whole.
+    ///
+    /// This re-renders the entire scene, since any number of sources may be affected.
+    pub fn update_group(
+        &mut self,
+        id: GroupId,
+        f: impl FnOnce(&mut Group),
+    ) -> Result<(), UnknownGroupError> {
+        let group = self.scene.group_mut(id).ok_or(UnknownGroupError(id))?;
+        f(group);
+        self.update_scene();
+
+        Ok(())
+    }
+
+    /// Remove a group, moving its members to its parent; see [Scene::remove_group].
+    pub fn remove_group(&mut self, id: GroupId) -> Option<Group> {
+        let group = self.scene.remove_group(id)?;
+        self.update_scene();
+
+        Some(group)
+    }
+
+    fn refresh_source(&mut self, id: SourceId, change: SceneChange) {
+        let perceived = self
+            .listener
+            .perceived_source(&self.scene, id)
+            .expect("source must be in the scene");
+
+        self.perceived_scene.put_source(id, perceived);
+        self.renderer.render_changes(&self.perceived_scene, change);
+    }
+
     fn update_scene(&mut self) {
-        let new_scene = self.listener.perceived_scene(&self.scene);
-        self.renderer.render_scene(&new_scene);
+        self.listener
+            .perceive_into(&self.scene, &mut self.perceived_scene);
+        self.renderer.render_scene(&self.perceived_scene);
     }
 
     pub fn renderer(&self) -> &T {
@@ -186,6 +895,7 @@ impl<T: Renderer> Soundscape<T> {
 mod tests {
     use std::f32::consts::*;
 
+    use approx::assert_relative_eq;
     use nalgebra::{point, Vector3};
 
     use super::*;
@@ -202,7 +912,11 @@ mod tests {
 
         let perceived = listener.perceived_scene(&scene);
 
-        assert_eq!(perceived.sources().first(), Some(&Source::new(expected)));
+        let expected = Source::with_location(expected)
+            .orientation(orientation.inverse())
+            .build();
+
+        assert_eq!(perceived.sources().first(), Some(&expected));
     }
 
     #[test]
@@ -210,4 +924,182 @@ mod tests {
         let listener = Listener::new(Orientation::from_axis_angle(&Vector3::y_axis(), 0.0));
         assert_eq!(listener.location(), Point3::origin());
     }
+
+    #[derive(Default)]
+    struct RecordingRenderer {
+        full_renders: usize,
+        changes: Vec<SceneChange>,
+        last_scene: Option<Scene>,
+    }
+
+    impl Renderer for RecordingRenderer {
+        fn render_scene(&mut self, scene: &Scene) {
+            self.full_renders += 1;
+            self.last_scene = Some(scene.clone());
+        }
+
+        fn render_changes(&mut self, scene: &Scene, change: SceneChange) {
+            self.changes.push(change);
+            self.last_scene = Some(scene.clone());
+        }
+    }
+
+    fn rotated_soundscape() -> Soundscape<RecordingRenderer> {
+        let orientation = Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2);
+        let scene = Scene::new(vec![
+            Source::new(point![1.0, 0.0, 0.0]),
+            Source::new(point![0.0, 1.0, 0.0]),
+        ]);
+
+        Soundscape::new(scene, orientation.into(), RecordingRenderer::default())
+    }
+
+    fn assert_rendered_perceived_scene(soundscape: &Soundscape<RecordingRenderer>) {
+        let expected = soundscape.listener().perceived_scene(soundscape.scene());
+        assert_eq!(soundscape.renderer().last_scene.as_ref(), Some(&expected));
+    }
+
+    #[test]
+    fn test_soundscape_add_source_renders_change_only() {
+        let mut soundscape = rotated_soundscape();
+
+        let id =
+            soundscape.add_source(Source::with_location([0.0, 0.0, 1.0]).name("voice").build());
+
+        assert_eq!(soundscape.renderer().full_renders, 1);
+        assert_eq!(soundscape.renderer().changes, [SceneChange::Added(id)]);
+        assert_eq!(soundscape.scene().find("voice"), Some(id));
+        assert_rendered_perceived_scene(&soundscape);
+    }
+
+    #[test]
+    fn test_soundscape_move_and_update_source() {
+        let mut soundscape = rotated_soundscape();
+        let id = soundscape.scene().ids()[1];
+
+        soundscape.move_source(id, [3.0, 2.0, 1.0]).unwrap();
+        soundscape
+            .update_source(id, |source| source.set_distance_gain(0.5))
+            .unwrap();
+
+        assert_eq!(
+            soundscape.renderer().changes,
+            [SceneChange::Updated(id), SceneChange::Updated(id)]
+        );
+        assert_eq!(soundscape.scene().source(id).unwrap().distance_gain(), 0.5);
+        assert_rendered_perceived_scene(&soundscape);
+    }
+
+    #[test]
+    fn test_soundscape_remove_source() {
+        let mut soundscape = rotated_soundscape();
+        let id = soundscape.scene().ids()[0];
+
+        assert!(soundscape.remove_source(id).is_some());
+        assert!(soundscape.remove_source(id).is_none());
+
+        assert_eq!(soundscape.renderer().changes, [SceneChange::Removed(id)]);
+        assert_eq!(soundscape.scene().len(), 1);
+        assert_rendered_perceived_scene(&soundscape);
+    }
+
+    #[test]
+    fn test_soundscape_unknown_source() {
+        let mut soundscape = rotated_soundscape();
+        let id = soundscape.scene().ids()[0];
+        soundscape.remove_source(id);
+
+        assert_eq!(
+            soundscape.move_source(id, [0.0, 0.0, 0.0]),
+            Err(UnknownSourceError(id))
+        );
+    }
+
+    #[test]
+    fn test_soundscape_group_edits() {
+        let mut soundscape = rotated_soundscape();
+        let group = soundscape.add_group(Group::default(), None).unwrap();
+        let id = soundscape
+            .add_grouped_source(Source::new([0.0, 0.0, 1.0]), group)
+            .unwrap();
+
+        soundscape
+            .update_group(group, |group| group.muted = true)
+            .unwrap();
+
+        assert_eq!(soundscape.renderer().changes, [SceneChange::Added(id)]);
+        assert_eq!(soundscape.renderer().full_renders, 2);
+        assert_rendered_perceived_scene(&soundscape);
+
+        let rendered = soundscape.renderer().last_scene.as_ref().unwrap();
+        assert_eq!(rendered.source(id).unwrap().gain(), 0.0);
+    }
+
+    #[test]
+    fn test_soundscape_listener_change_keeps_edits() {
+        let mut soundscape = rotated_soundscape();
+        let id = soundscape.add_source(Source::new([0.0, 0.0, 1.0]));
+
+        soundscape.set_listener(Orientation::identity().into());
+
+        assert_eq!(soundscape.renderer().full_renders, 2);
+        assert!(soundscape
+            .renderer()
+            .last_scene
+            .as_ref()
+            .unwrap()
+            .source(id)
+            .is_some());
+        assert_rendered_perceived_scene(&soundscape);
+    }
+
+    #[test]
+    fn test_soundscape_listener_smoothing() {
+        let mut soundscape = rotated_soundscape();
+        let start = soundscape.listener().orientation();
+        let target = Listener::new(Orientation::identity());
+        soundscape.set_listener_smoothing(Some(Duration::from_millis(100)));
+
+        soundscape.set_listener(target.clone());
+
+        assert!(!soundscape.is_settled());
+        assert_eq!(soundscape.renderer().full_renders, 1);
+        assert_eq!(soundscape.listener().orientation(), start);
+        assert_eq!(
+            soundscape.target_listener().orientation(),
+            target.orientation()
+        );
+
+        soundscape.advance(Duration::from_millis(50));
+
+        assert_eq!(soundscape.renderer().full_renders, 2);
+        assert_relative_eq!(
+            soundscape.listener().orientation().angle(),
+            FRAC_PI_4,
+            epsilon = 1e-6
+        );
+        assert_rendered_perceived_scene(&soundscape);
+
+        soundscape.advance(Duration::from_millis(60));
+        soundscape.advance(Duration::from_millis(10));
+
+        assert!(soundscape.is_settled());
+        assert_eq!(soundscape.renderer().full_renders, 3);
+        assert_eq!(soundscape.listener().orientation(), target.orientation());
+        assert_rendered_perceived_scene(&soundscape);
+    }
+
+    #[test]
+    fn test_soundscape_disabling_smoothing_jumps_to_target() {
+        let mut soundscape = rotated_soundscape();
+        soundscape.set_listener_smoothing(Some(Duration::from_secs(1)));
+        soundscape.set_listener(Orientation::identity().into());
+        soundscape.advance(Duration::from_millis(100));
+
+        soundscape.set_listener_smoothing(None);
+
+        assert!(soundscape.is_settled());
+        assert_eq!(soundscape.listener().orientation(), Orientation::identity());
+        assert_rendered_perceived_scene(&soundscape);
+    }
 }
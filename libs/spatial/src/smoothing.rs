@@ -0,0 +1,117 @@
+//! # Listener smoothing
+//!
+//! Head trackers are usually sampled much less often than the audio is rendered, so jumping
+//! straight to every new listener pose makes the rotations audibly stepped. Instead, the listener
+//! may be moved towards the new pose gradually, over a fixed period of time.
+
+use std::time::Duration;
+
+use crate::Listener;
+
+/// Transition of the listener towards the most recently set pose.
+#[derive(Debug, Clone)]
+pub(crate) struct Transition {
+    from: Listener,
+    to: Listener,
+    elapsed: Duration,
+}
+
+impl Listener {
+    /// Interpolate between this listener and `other`: the orientation is interpolated spherically,
+    /// the location and velocity are interpolated linearly.
+    ///
+    /// `t` is clamped to `[0, 1]`, where zero gives this listener and one gives `other`.
+    pub fn interpolate(&self, other: &Listener, t: f32) -> Listener {
+        let t = t.clamp(0.0, 1.0);
+
+        // Interpolation is ill-defined for the opposite orientations, so just pick the closest one
+        let orientation = self
+            .orientation
+            .try_slerp(&other.orientation, t, f32::EPSILON)
+            .unwrap_or(if t < 0.5 {
+                self.orientation
+            } else {
+                other.orientation
+            });
+
+        Listener {
+            location: self.location.coords.lerp(&other.location.coords, t).into(),
+            orientation,
+            velocity: self.velocity.lerp(&other.velocity, t),
+        }
+    }
+}
+
+impl Transition {
+    pub(crate) fn new(from: Listener, to: Listener) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn target(&self) -> &Listener {
+        &self.to
+    }
+
+    /// Move the transition forward by `elapsed`; returns the listener at the new point in time
+    /// and whether the target has been reached.
+    pub(crate) fn advance(&mut self, elapsed: Duration, duration: Duration) -> (Listener, bool) {
+        self.elapsed += elapsed;
+
+        if self.elapsed >= duration {
+            return (self.to.clone(), true);
+        }
+
+        let t = self.elapsed.as_secs_f32() / duration.as_secs_f32();
+
+        (self.from.interpolate(&self.to, t), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::*;
+
+    use approx::assert_relative_eq;
+    use irt_lin_alg::na::{point, Vector3};
+
+    use super::*;
+    use crate::Orientation;
+
+    #[test]
+    fn test_interpolate() {
+        let from = Listener::new(Orientation::identity());
+        let to = Listener::new_with_location(
+            point![2.0, 0.0, 0.0],
+            Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2),
+        );
+
+        let halfway = from.interpolate(&to, 0.5);
+
+        assert_relative_eq!(halfway.location(), point![1.0, 0.0, 0.0]);
+        assert_relative_eq!(
+            halfway.orientation(),
+            Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_4),
+            epsilon = 1e-6
+        );
+        assert_eq!(from.interpolate(&to, 2.0).location(), to.location());
+    }
+
+    #[test]
+    fn test_transition() {
+        let from = Listener::new(Orientation::identity());
+        let to = Listener::new(Orientation::from_axis_angle(&Vector3::z_axis(), PI / 3.0));
+        let duration = Duration::from_millis(90);
+        let mut transition = Transition::new(from, to.clone());
+
+        let (listener, finished) = transition.advance(Duration::from_millis(30), duration);
+        assert!(!finished);
+        assert_relative_eq!(listener.orientation().angle(), PI / 9.0, epsilon = 1e-6);
+
+        let (listener, finished) = transition.advance(Duration::from_millis(70), duration);
+        assert!(finished);
+        assert_eq!(listener.orientation(), to.orientation());
+    }
+}
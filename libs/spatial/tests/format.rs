@@ -0,0 +1,169 @@
+use std::f32::consts::*;
+
+use na::{point, Vector3};
+use nalgebra as na;
+
+use irt_lin_alg::Orientation;
+use irt_spatial::format::{self, Format, LoadError, Problem, Subject};
+use irt_spatial::{Attenuation, Cone, Curve, DistanceModel, Group, Scene, Source, Transform};
+
+fn sample_scene() -> Scene {
+    let mut scene = Scene::new(vec![Source::with_location(point![0.0, 1.0, 0.0])
+        .name("vocals")
+        .distance_gain(0.5)
+        .attenuation(Attenuation {
+            model: DistanceModel::Custom(Curve::new(vec![(0.0, 1.0), (10.0, 0.1)])),
+            ..Default::default()
+        })
+        .orientation(Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_4))
+        .cone(Cone::new(FRAC_PI_2, PI, 0.2))
+        .velocity(Vector3::new(0.0, -1.0, 0.0))
+        .build()]);
+
+    let stage = scene
+        .add_group(
+            Group::named(
+                "stage",
+                Transform::new(Vector3::new(0.0, 5.0, 0.0), Vector3::z() * FRAC_PI_2, 2.0),
+            ),
+            None,
+        )
+        .unwrap();
+    let drums = scene
+        .add_group(Group::named("drums", Transform::identity()), Some(stage))
+        .unwrap();
+
+    scene
+        .add_grouped_source(
+            Source::with_location(point![1.0, 0.0, 0.0])
+                .name("kick")
+                .attenuation(DistanceModel::Inverse)
+                .build(),
+            drums,
+        )
+        .unwrap();
+
+    scene
+}
+
+#[test]
+fn test_json_round_trip() {
+    let scene = sample_scene();
+
+    let text = format::to_string(&scene, Format::Json).unwrap();
+    let loaded = format::from_str(&text, Format::Json).unwrap();
+
+    assert_eq!(loaded, scene);
+}
+
+#[test]
+fn test_toml_round_trip() {
+    let scene = sample_scene();
+
+    let text = format::to_string(&scene, Format::Toml).unwrap();
+    let loaded = format::from_str(&text, Format::Toml).unwrap();
+
+    assert_eq!(loaded, scene);
+}
+
+#[test]
+fn test_defaults_and_implicit_ids() {
+    let text = r#"
+        version = 1
+
+        [[sources]]
+        id = 4
+        position = [1.0, 0.0, 0.0]
+
+        [[sources]]
+        name = "unnamed id"
+        position = [0, 2, 0]
+    "#;
+
+    let scene = format::from_str(text, Format::Toml).unwrap();
+    let ids: Vec<_> = scene.ids().iter().map(|id| id.value()).collect();
+
+    assert_eq!(ids, [4, 5]);
+    assert_eq!(
+        scene.source(scene.find("unnamed id").unwrap()).unwrap(),
+        &Source::with_location(point![0.0, 2.0, 0.0])
+            .name("unnamed id")
+            .build()
+    );
+}
+
+#[test]
+fn test_unsupported_version() {
+    let text = r#"{ "version": 42, "sources": "not even a list" }"#;
+
+    let error = format::from_str(text, Format::Json).unwrap_err();
+
+    assert!(matches!(error, LoadError::UnsupportedVersion(42)));
+}
+
+#[test]
+fn test_validation_error_points_at_source() {
+    let text = r#"{
+        "version": 1,
+        "sources": [
+            { "position": [0.0, 1.0, 0.0] },
+            { "name": "too loud", "position": [0.0, 1.0, 0.0], "distance_gain": -2.0 }
+        ]
+    }"#;
+
+    let LoadError::Invalid(error) = format::from_str(text, Format::Json).unwrap_err() else {
+        panic!("expected a validation error");
+    };
+
+    assert_eq!(
+        error.subject,
+        Subject::Source {
+            index: 1,
+            name: Some("too loud".to_owned())
+        }
+    );
+    assert_eq!(error.problem, Problem::Negative("distance_gain"));
+    assert_eq!(
+        error.to_string(),
+        "sources[1] (\"too loud\"): `distance_gain` must not be negative"
+    );
+}
+
+#[test]
+fn test_group_validation() {
+    let cycle = r#"
+        version = 1
+
+        [[groups]]
+        id = 0
+        parent = 1
+
+        [[groups]]
+        id = 1
+        parent = 0
+    "#;
+    let unknown = r#"
+        version = 1
+
+        [[sources]]
+        position = [0.0, 1.0, 0.0]
+        group = 3
+    "#;
+
+    let LoadError::Invalid(cycle) = format::from_str(cycle, Format::Toml).unwrap_err() else {
+        panic!("expected a validation error");
+    };
+    let LoadError::Invalid(unknown) = format::from_str(unknown, Format::Toml).unwrap_err() else {
+        panic!("expected a validation error");
+    };
+
+    assert_eq!(cycle.problem, Problem::Cycle);
+    assert!(matches!(unknown.problem, Problem::UnknownGroup(id) if id.value() == 3));
+}
+
+#[test]
+fn test_format_from_path() {
+    assert_eq!(Format::from_path("scene.json"), Some(Format::Json));
+    assert_eq!(Format::from_path("dir/scene.TOML"), Some(Format::Toml));
+    assert_eq!(Format::from_path("scene.yaml"), None);
+}
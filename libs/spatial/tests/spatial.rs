@@ -5,7 +5,10 @@ use na::{point, Vector3};
 use nalgebra as na;
 
 use irt_lin_alg::Orientation;
-use irt_spatial::{Listener, Scene, Source};
+use irt_spatial::{
+    Attenuation, Cone, DistanceModel, Doppler, Group, GroupError, Listener, Scene, Source,
+    Transform, UnknownGroupError,
+};
 
 #[test]
 fn test_point_rotation() {
@@ -30,7 +33,11 @@ fn test_scene_rotation() {
     let rotated_points = scene_points.map(|p| orientation * p);
 
     let initial_scene = Scene::new(scene_points.map(Source::new).into());
-    let rotated_scene = Scene::new(rotated_points.map(Source::new).into());
+    let rotated_scene = Scene::new(
+        rotated_points
+            .map(|p| Source::with_location(p).orientation(orientation).build())
+            .into(),
+    );
 
     assert_eq!(initial_scene.relative_to(orientation), rotated_scene);
 }
@@ -77,3 +84,438 @@ fn test_listener_with_location_perceived_scene() {
 
     assert!(offset_by_position);
 }
+
+/// Builds a source with every attribute set to a non-default value.
+///
+/// Extend this whenever [Source] gets a new attribute, so that the tests below
+/// keep verifying that perception only ever changes the position, orientation, velocity and
+/// the computed values.
+fn fully_attributed_source(position: na::Point3<f32>) -> Source {
+    Source::with_location(position)
+        .distance_gain(0.25)
+        .name("fully attributed")
+        .attenuation(Attenuation {
+            model: DistanceModel::Linear,
+            reference_distance: 0.5,
+            max_distance: 20.0,
+            rolloff: 0.8,
+        })
+        .orientation(Orientation::from_axis_angle(&Vector3::x_axis(), FRAC_PI_6))
+        .cone(Cone::new(FRAC_PI_2, PI, 0.3))
+        .velocity(Vector3::new(1.0, -2.0, 0.5))
+        .build()
+}
+
+#[test]
+fn test_source_perceived_from_preserves_attributes() {
+    let source = fully_attributed_source(point![-1.0, 2.0, 0.0]);
+    let orientation = Orientation::from_axis_angle(&Vector3::z_axis(), -FRAC_PI_2);
+
+    let mut perceived = source.perceived_from(&orientation);
+
+    assert_relative_eq!(perceived.location(), orientation * source.location());
+    assert_relative_eq!(perceived.orientation(), orientation * source.orientation());
+    assert_relative_eq!(perceived.velocity(), orientation * source.velocity());
+    assert_eq!(perceived.distance_gain(), source.distance_gain());
+
+    perceived.set_location(source.location());
+    perceived.set_orientation(source.orientation());
+    perceived.set_velocity(source.velocity());
+    assert_eq!(perceived, source);
+}
+
+#[test]
+fn test_listener_perceived_scene_preserves_attributes() {
+    let position = point![1.0, 2.0, 1.0];
+    let orientation = Orientation::from_axis_angle(&Vector3::y_axis(), FRAC_PI_4);
+    let listener = Listener::new_with_location(position, orientation);
+
+    let sources = [point![2.0, -1.0, 2.0], point![4.0, 2.0, -4.0]].map(fully_attributed_source);
+    let scene = Scene::new(sources.clone().into());
+
+    let perceived_scene = listener.perceived_scene(&scene);
+
+    assert_eq!(perceived_scene.sources().len(), sources.len());
+
+    for (perceived, original) in perceived_scene.sources().iter().zip(sources.iter()) {
+        let expected = orientation.inverse() * (original.location() - position.coords);
+        assert_relative_eq!(perceived.location(), expected, epsilon = 1e-6);
+        assert_relative_eq!(
+            perceived.orientation(),
+            orientation.inverse() * original.orientation(),
+            epsilon = 1e-6
+        );
+        assert_eq!(perceived.distance_gain(), original.distance_gain());
+
+        let mut restored = perceived.relocated(original.location()).authored();
+        restored.set_orientation(original.orientation());
+        restored.set_velocity(original.velocity());
+        assert_eq!(&restored, original);
+    }
+}
+
+#[test]
+fn test_identity_perception_is_lossless() {
+    let listener = Listener::new(Orientation::identity());
+    let scene = Scene::new(vec![
+        fully_attributed_source(point![-1.0, 2.0, 0.0]),
+        fully_attributed_source(point![0.0, 3.0, 2.0]),
+    ]);
+
+    let perceived_scene = listener.perceived_scene(&scene);
+    let authored: Vec<_> = perceived_scene
+        .sources()
+        .iter()
+        .map(Source::authored)
+        .collect();
+
+    assert_eq!(perceived_scene.ids(), scene.ids());
+    assert_eq!(authored, scene.sources());
+    assert_eq!(scene.relative_to(Orientation::identity()), scene);
+}
+
+#[test]
+fn test_perceived_scene_applies_distance_attenuation() {
+    let listener = Listener::new_with_location(
+        point![1.0, 1.0, 0.0],
+        Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2),
+    );
+    let attenuation = Attenuation::new(DistanceModel::Inverse);
+    let scene = Scene::new(vec![
+        Source::with_location(point![1.0, 5.0, 0.0])
+            .distance_gain(0.5)
+            .attenuation(attenuation.clone())
+            .build(),
+        Source::with_location(point![1.0, 5.0, 0.0])
+            .distance_gain(0.5)
+            .build(),
+    ]);
+
+    let perceived_scene = listener.perceived_scene(&scene);
+    let [attenuated, constant] = perceived_scene.sources() else {
+        panic!("expected two sources");
+    };
+
+    assert_relative_eq!(attenuated.perception().distance_attenuation(), 0.25);
+    assert_relative_eq!(attenuated.gain(), 0.125);
+    assert_eq!(constant.gain(), 0.5);
+
+    // the authored scene itself is never attenuated
+    assert_eq!(scene.sources()[0].gain(), 0.5);
+}
+
+#[test]
+fn test_scene_ids_are_stable() {
+    let mut scene = Scene::new(vec![
+        Source::new(point![1.0, 0.0, 0.0]),
+        Source::new(point![0.0, 1.0, 0.0]),
+    ]);
+    let [first, second] = [scene.ids()[0], scene.ids()[1]];
+
+    assert!(scene.remove_source(first).is_some());
+    let third = scene.add_source(Source::new(point![0.0, 0.0, 1.0]));
+
+    assert_ne!(third, first);
+    assert_eq!(scene.ids(), [second, third]);
+    assert_eq!(
+        scene.source(second).unwrap().location(),
+        point![0.0, 1.0, 0.0]
+    );
+    assert_eq!(scene.source(first), None);
+}
+
+#[test]
+fn test_scene_find_by_name() {
+    let mut scene = Scene::new(vec![Source::new(point![1.0, 0.0, 0.0])]);
+    let id = scene.add_source(
+        Source::with_location(point![0.0, 1.0, 0.0])
+            .name("narrator")
+            .build(),
+    );
+
+    assert_eq!(scene.find("narrator"), Some(id));
+    assert_eq!(scene.find("missing"), None);
+    assert_eq!(scene.source(id).unwrap().name(), Some("narrator"));
+}
+
+#[test]
+fn test_perceived_scene_keeps_ids() {
+    let mut scene = Scene::new(vec![
+        Source::new(point![1.0, 0.0, 0.0]),
+        Source::new(point![0.0, 1.0, 0.0]),
+    ]);
+    scene.remove_source(scene.ids()[0]);
+    scene.add_source(Source::new(point![0.0, 0.0, 1.0]));
+
+    let listener = Listener::new(Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2));
+
+    assert_eq!(listener.perceived_scene(&scene).ids(), scene.ids());
+}
+
+#[test]
+fn test_perceived_scene_applies_directivity() {
+    let cone = Cone::new(FRAC_PI_2, PI, 0.1);
+    let location = point![0.0, 2.0, 0.0];
+
+    // Both speakers are in front of the listener: one faces away, the other one faces the listener
+    let scene = Scene::new(vec![
+        Source::with_location(location).cone(cone.clone()).build(),
+        Source::with_location(location)
+            .cone(cone)
+            .orientation(Orientation::from_axis_angle(&Vector3::z_axis(), PI))
+            .build(),
+    ]);
+
+    let listener = Listener::new(Orientation::identity());
+    let perceived_scene = listener.perceived_scene(&scene);
+    let [away, towards] = perceived_scene.sources() else {
+        panic!("expected two sources");
+    };
+
+    assert_relative_eq!(away.perception().directivity_gain(), 0.1);
+    assert_relative_eq!(away.gain(), 0.1);
+    assert_relative_eq!(towards.perception().directivity_gain(), 1.0);
+    assert_relative_eq!(towards.forward(), -Vector3::y(), epsilon = 1e-6);
+}
+
+#[test]
+fn test_perceived_scene_applies_doppler() {
+    let mut scene = Scene::new(vec![
+        Source::with_location(point![0.0, 10.0, 0.0])
+            .velocity(Vector3::new(0.0, -10.0, 0.0))
+            .build(),
+        Source::new(point![0.0, 10.0, 0.0]),
+    ]);
+    scene.set_doppler(Doppler {
+        speed_of_sound: 100.0,
+        scale: 2.0,
+    });
+
+    let listener = Listener::new(Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_3))
+        .with_velocity(Vector3::new(0.0, 5.0, 0.0));
+
+    let perceived_scene = listener.perceived_scene(&scene);
+    let [moving, still] = perceived_scene.sources() else {
+        panic!("expected two sources");
+    };
+
+    assert_relative_eq!(moving.perception().doppler_factor(), 110.0 / 80.0);
+    assert_relative_eq!(still.perception().doppler_factor(), 110.0 / 100.0);
+    assert_eq!(perceived_scene.doppler(), scene.doppler());
+}
+
+#[test]
+fn test_nested_group_transforms() {
+    let mut scene = Scene::new(vec![Source::new(point![0.0, 5.0, 0.0])]);
+
+    let stage = scene
+        .add_group(
+            Group::named(
+                "stage",
+                Transform::new(Vector3::new(0.0, 10.0, 0.0), Vector3::z() * FRAC_PI_2, 1.0),
+            ),
+            None,
+        )
+        .unwrap();
+    let drums = scene
+        .add_group(
+            Group::named(
+                "drums",
+                Transform::new(Vector3::new(2.0, 0.0, 0.0), Vector3::zeros(), 2.0),
+            ),
+            Some(stage),
+        )
+        .unwrap();
+
+    let kick = scene
+        .add_grouped_source(
+            Source::with_location(point![1.0, 0.0, 0.0])
+                .velocity(Vector3::new(1.0, 0.0, 0.0))
+                .build(),
+            drums,
+        )
+        .unwrap();
+
+    let resolved = scene.resolved();
+    let kick = resolved.source(kick).unwrap();
+
+    // drums: (1, 0, 0) -> (4, 0, 0); stage: rotated by 90° around z -> (0, 4, 0) -> (0, 14, 0)
+    assert_relative_eq!(kick.location(), point![0.0, 14.0, 0.0], epsilon = 1e-5);
+    assert_relative_eq!(kick.velocity(), Vector3::new(0.0, 2.0, 0.0), epsilon = 1e-5);
+    assert_relative_eq!(
+        kick.orientation(),
+        Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2),
+        epsilon = 1e-6
+    );
+    assert_eq!(scene.find_group("drums"), Some(drums));
+    assert_eq!(resolved.groups().count(), 0);
+
+    // Ungrouped sources are left intact
+    assert_eq!(resolved.sources()[0], scene.sources()[0]);
+}
+
+#[test]
+fn test_perceived_scene_resolves_groups() {
+    let mut scene = Scene::new(vec![]);
+    let group = scene
+        .add_group(
+            Group::new(Transform::new(
+                Vector3::new(0.0, 3.0, 0.0),
+                Vector3::zeros(),
+                1.0,
+            )),
+            None,
+        )
+        .unwrap();
+    let id = scene
+        .add_grouped_source(Source::new(point![1.0, 0.0, 0.0]), group)
+        .unwrap();
+
+    let listener = Listener::new_with_location(point![1.0, 1.0, 0.0], Orientation::identity());
+
+    assert_relative_eq!(
+        listener
+            .perceived_scene(&scene)
+            .source(id)
+            .unwrap()
+            .location(),
+        point![0.0, 2.0, 0.0]
+    );
+    assert_eq!(
+        listener.perceived_scene(&scene),
+        listener.perceived_scene(&scene.resolved())
+    );
+}
+
+#[test]
+fn test_group_gain_and_mute() {
+    let mut scene = Scene::new(vec![]);
+    let outer = scene
+        .add_group(
+            Group {
+                gain: 0.5,
+                ..Default::default()
+            },
+            None,
+        )
+        .unwrap();
+    let inner = scene
+        .add_group(
+            Group {
+                gain: 0.5,
+                ..Default::default()
+            },
+            Some(outer),
+        )
+        .unwrap();
+    let id = scene
+        .add_grouped_source(
+            Source::with_location(point![0.0, 1.0, 0.0])
+                .distance_gain(0.8)
+                .build(),
+            inner,
+        )
+        .unwrap();
+
+    assert_relative_eq!(scene.resolved().source(id).unwrap().gain(), 0.2);
+
+    scene.group_mut(outer).unwrap().muted = true;
+    assert_eq!(scene.resolved().source(id).unwrap().gain(), 0.0);
+}
+
+#[test]
+fn test_remove_group_moves_members_to_parent() {
+    let mut scene = Scene::new(vec![]);
+    let outer = scene.add_group(Group::default(), None).unwrap();
+    let inner = scene.add_group(Group::default(), Some(outer)).unwrap();
+    let nested = scene.add_group(Group::default(), Some(inner)).unwrap();
+    let id = scene
+        .add_grouped_source(Source::new(point![0.0, 1.0, 0.0]), inner)
+        .unwrap();
+
+    assert!(scene.remove_group(inner).is_some());
+
+    assert_eq!(scene.source_group(id), Some(outer));
+    assert_eq!(scene.group_parent(nested), Some(outer));
+
+    assert!(scene.remove_group(outer).is_some());
+
+    assert_eq!(scene.source_group(id), None);
+    assert_eq!(scene.group_parent(nested), None);
+}
+
+#[test]
+fn test_unknown_group() {
+    let mut scene = Scene::new(vec![Source::new(point![0.0, 1.0, 0.0])]);
+    let group = scene.add_group(Group::default(), None).unwrap();
+    scene.remove_group(group);
+
+    assert_eq!(
+        scene.add_group(Group::default(), Some(group)),
+        Err(UnknownGroupError(group))
+    );
+    assert_eq!(
+        scene.set_source_group(scene.ids()[0], Some(group)),
+        Err(GroupError::UnknownGroup(UnknownGroupError(group)))
+    );
+}
+
+#[test]
+fn test_perceive_into_reuses_scene() {
+    let mut scene = Scene::new(vec![
+        fully_attributed_source(point![1.0, 2.0, 3.0]),
+        Source::new(point![0.0, 3.0, 0.0]),
+    ]);
+    let group = scene
+        .add_group(
+            Group::new(Transform::new(
+                Vector3::new(0.0, 1.0, 0.0),
+                Vector3::zeros(),
+                2.0,
+            )),
+            None,
+        )
+        .unwrap();
+    scene
+        .add_grouped_source(fully_attributed_source(point![-1.0, 0.0, 0.0]), group)
+        .unwrap();
+
+    let listener = Listener::new_with_location(
+        point![0.5, -1.0, 0.0],
+        Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_3),
+    )
+    .with_velocity(Vector3::new(0.0, 2.0, 0.0));
+
+    // Start from a stale scene of a different size to make sure nothing is left behind
+    let mut out = Scene::new(vec![Source::new(point![9.0, 9.0, 9.0]); 5]);
+    listener.perceive_into(&scene, &mut out);
+    assert_eq!(out, listener.perceived_scene(&scene));
+
+    scene.remove_source(scene.ids()[1]);
+    listener.perceive_into(&scene, &mut out);
+    assert_eq!(out, listener.perceived_scene(&scene));
+}
+
+#[test]
+fn test_perceived_spherical_location() {
+    // The listener stands at (0, -1, 0) and looks to the left (towards -x)
+    let listener = Listener::new_with_location(
+        point![0.0, -1.0, 0.0],
+        Orientation::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2),
+    );
+    let scene = Scene::new(vec![
+        Source::new(point![-2.0, -1.0, 0.0]),
+        Source::new(point![0.0, 1.0, 0.0]),
+        Source::new(point![0.0, -1.0, 3.0]),
+    ]);
+
+    let perceived = listener.perceived_scene(&scene);
+    let [front, right, above] = [0, 1, 2].map(|i| perceived.sources()[i].spherical_location());
+
+    assert_relative_eq!(front.azimuth, 0.0, epsilon = 1e-6);
+    assert_relative_eq!(front.distance, 2.0, epsilon = 1e-6);
+    assert_relative_eq!(right.azimuth, FRAC_PI_2, epsilon = 1e-6);
+    assert_relative_eq!(right.elevation, 0.0, epsilon = 1e-6);
+    assert_relative_eq!(above.elevation, FRAC_PI_2, epsilon = 1e-6);
+    assert_relative_eq!(above.distance, 3.0, epsilon = 1e-6);
+}